@@ -1,23 +1,117 @@
-use clap::{arg, Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 pub struct Cli {
     #[command(subcommand)]
     pub commands: Commands,
+
+    /// How to report progress. "json" writes one JSON event per line to stdout
+    #[arg(long, global = true, value_enum, default_value_t = ProgressMode::Bar)]
+    pub progress: ProgressMode,
+
+    #[command(flatten)]
+    pub cache: CacheOptions,
+
+    #[command(flatten)]
+    pub scraper: ScraperOptions,
+
+    /// A path to a directory where every HTTP request and response is recorded as a fixture
+    #[arg(long, global = true, conflicts_with = "replay")]
+    pub record: Option<String>,
+
+    /// A path to a directory of fixtures recorded with `--record`. Responses are served from it without network access
+    #[arg(long, global = true)]
+    pub replay: Option<String>,
+
+    /// Maximum number of requests per second sent to the Genius API. If not specified, requests are not limited
+    #[arg(long, global = true)]
+    pub rate_limit: Option<u32>,
+}
+
+#[derive(Args)]
+pub struct CacheOptions {
+    /// Serve Genius API responses and song pages from the cache only, failing on a miss
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Do not read from or write to the on-disk cache
+    #[arg(long, global = true, conflicts_with = "offline")]
+    pub no_cache: bool,
+
+    /// A path to the cache directory. If not specified, `~/.cache/lyri` is used
+    #[arg(long, global = true)]
+    pub cache_dir: Option<String>,
+
+    /// Number of hours after which cached responses are revalidated
+    #[arg(long, global = true, default_value_t = 24)]
+    pub cache_ttl: u64,
+
+    /// Maximum size of the cache in megabytes. The oldest entries are evicted first
+    #[arg(long, global = true, default_value_t = 1024)]
+    pub cache_max_size: u64,
+}
+
+#[derive(Args)]
+pub struct ScraperOptions {
+    /// User agent sent when scraping song pages, also used to match robots.txt rules
+    #[arg(long, global = true, default_value = concat!("lyri/", env!("CARGO_PKG_VERSION")))]
+    pub user_agent: String,
+
+    /// Maximum number of song pages requested per second from each host. If not specified, requests are not limited
+    #[arg(long, global = true)]
+    pub scrape_rate_limit: Option<u32>,
+
+    /// Number of seconds after which a song page request is abandoned
+    #[arg(long, global = true)]
+    pub scrape_timeout: Option<u64>,
+
+    /// Maximum number of song pages scraped at once
+    #[arg(long, global = true, default_value_t = 50)]
+    pub scrape_concurrency: usize,
+
+    /// Skip song pages disallowed by the site's robots.txt
+    #[arg(long, global = true)]
+    pub respect_robots: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ProgressMode {
+    /// Progress bars in the terminal
+    Bar,
+    /// JSON lines on stdout
+    Json,
+    /// No progress reporting
+    None,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Retrieves lyrics for a specific artist.
     Artist(ArtistArgs),
+    /// Retrieves lyrics for every track of an album, in album order.
+    Album(AlbumArgs),
+    /// Manages Genius credentials.
+    Auth(AuthArgs),
+    /// Manages the on-disk cache of API responses and song pages.
+    Cache(CacheArgs),
+    /// Extracts lyrics from song pages saved to disk, without network access.
+    ScrapeLocal(ScrapeLocalArgs),
 }
 
 #[derive(Args)]
 pub struct ArtistArgs {
-    /// Name of the artist
-    #[arg(short, long)]
-    pub name: String,
+    /// Name of the artist. The closest match among the Genius search hits is used
+    #[arg(short, long, required_unless_present = "artist_id")]
+    pub name: Option<String>,
+
+    /// Genius ID of the artist, used instead of searching for the name
+    #[arg(long, conflicts_with_all = ["name", "interactive"])]
+    pub artist_id: Option<u32>,
+
+    /// Choose the artist among the best matches for the name instead of using the best one
+    #[arg(long)]
+    pub interactive: bool,
 
     /// Number of songs to retrieve. If not specified, all songs will be retrieved
     #[arg(short, long)]
@@ -38,4 +132,128 @@ pub struct ArtistArgs {
     /// A path to the directory where the lyrics will be saved. If not specified, the lyrics will be saved in a new file in the current directory.
     #[arg(short, long)]
     pub output_dir: Option<String>,
+
+    /// Number of pages of songs to fetch concurrently. If not specified, pages are fetched one after another
+    #[arg(long)]
+    pub page_concurrency: Option<usize>,
+
+    /// A path to a directory of lyrics files laid out as `<artist>/<title>.txt` or `<artist>/<title>.lrc`. Song pages are only scraped for songs without a file
+    #[arg(long)]
+    pub lyrics_dir: Option<String>,
+
+    #[command(flatten)]
+    pub lyrics: LyricsOptions,
+}
+
+#[derive(Args)]
+pub struct AlbumArgs {
+    /// URL of the Genius album page
+    #[arg(short, long, required_unless_present_all = ["artist", "title"], conflicts_with_all = ["artist", "title"])]
+    pub url: Option<String>,
+
+    /// Name of the artist of the album, used with `--title` to find its page
+    #[arg(short, long, requires = "title")]
+    pub artist: Option<String>,
+
+    /// Title of the album, used with `--artist` to find its page
+    #[arg(short, long, requires = "artist")]
+    pub title: Option<String>,
+
+    /// A path to the directory where the lyrics will be saved. If not specified, the lyrics will be saved in a new file in the current directory.
+    #[arg(short, long)]
+    pub output_dir: Option<String>,
+
+    #[command(flatten)]
+    pub lyrics: LyricsOptions,
+}
+
+#[derive(Args)]
+pub struct ScrapeLocalArgs {
+    /// A path to a saved song page, or to a directory searched recursively for `.html` and `.htm` files
+    pub path: String,
+
+    /// A path to the file where the lyrics will be saved. If not specified, a file named after `PATH` is created in the current directory
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    #[command(flatten)]
+    pub lyrics: LyricsOptions,
+}
+
+#[derive(Args)]
+pub struct LyricsOptions {
+    /// Also save the lyrics split into sections such as verses and choruses
+    #[arg(long)]
+    pub sections: bool,
+
+    /// Also save the annotated fragments of the lyrics, with their character offsets and referent IDs
+    #[arg(long)]
+    pub annotations: bool,
+
+    /// Keep page boilerplate such as "You might also like" blocks and "Embed" trailers in the lyrics
+    #[arg(long)]
+    pub no_clean: bool,
+
+    /// Also save the album, tags, credits and page views embedded in each song page
+    #[arg(long)]
+    pub metadata: bool,
+
+    /// Also save the lyrics of the community translations linked from each song page, by language
+    #[arg(long)]
+    pub translations: bool,
+
+    /// A path to a directory where each song is also saved as `<artist>/<title>.lrc`, with the timings of LRC files read from `--lyrics-dir`
+    #[arg(long)]
+    pub export_lrc: Option<String>,
+}
+
+#[derive(Args)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    pub command: AuthCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommands {
+    /// Validates the configured access token against the Genius API.
+    Check,
+    /// Obtains a user access token through the OAuth2 authorization-code flow and stores it.
+    Login(LoginArgs),
+    /// Removes the stored user access token.
+    Logout,
+}
+
+#[derive(Args)]
+pub struct LoginArgs {
+    /// Client ID of the Genius API client
+    #[arg(long, env = "GENIUS_CLIENT_ID")]
+    pub client_id: String,
+
+    /// Client secret of the Genius API client
+    #[arg(long, env = "GENIUS_CLIENT_SECRET", hide_env_values = true)]
+    pub client_secret: String,
+
+    /// Local port of the redirect listener. The redirect URI `http://127.0.0.1:<PORT>/callback` must be registered for the API client
+    #[arg(short, long, default_value_t = 8765)]
+    pub port: u16,
+
+    /// Comma-separated scopes to request. Supported values: "me", "create_annotation", "manage_annotation" and "vote"
+    #[arg(short, long, value_delimiter = ',', default_value = "me")]
+    pub scopes: Vec<String>,
+}
+
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Prints the number of entries and the size of the cache.
+    Stats,
+    /// Removes expired entries and evicts the oldest ones above the size limit.
+    Prune,
+    /// Removes every entry.
+    Clear,
 }
@@ -1,5 +1,6 @@
-use std::time::Duration;
+use std::{sync::Mutex, time::Duration};
 
+use genius::{error::GeniusError, progress::ProgressObserver};
 use indicatif::{ProgressBar, ProgressStyle};
 
 pub fn fetch_progress_bar() -> ProgressBar {
@@ -35,3 +36,48 @@ pub fn scrape_progress_bar(n: u16) -> ProgressBar {
     );
     spinner
 }
+
+pub fn hidden_progress_bar() -> ProgressBar {
+    ProgressBar::hidden()
+}
+
+/// Draws a spinner while the Genius client paginates. The spinner is created on the first
+/// fetched page and cleared once pagination ends.
+#[derive(Default)]
+pub struct FetchProgressObserver {
+    spinner: Mutex<Option<ProgressBar>>,
+}
+
+impl FetchProgressObserver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_spinner(&self, f: impl FnOnce(&ProgressBar)) {
+        let mut spinner = self.spinner.lock().unwrap();
+        f(spinner.get_or_insert_with(fetch_progress_bar));
+    }
+}
+
+impl ProgressObserver for FetchProgressObserver {
+    fn on_page(&self, _endpoint: &str, _page: u32, _items: usize, total: usize) {
+        self.with_spinner(|spinner| spinner.set_message(format!("Fetching songs... ({} fetched)", total)));
+    }
+
+    fn on_retry(&self, _endpoint: &str, attempt: u32, delay: Duration, error: &GeniusError) {
+        self.with_spinner(|spinner| {
+            spinner.set_message(format!(
+                "Retrying in {:.1}s (attempt {}): {}",
+                delay.as_secs_f32(),
+                attempt,
+                error
+            ))
+        });
+    }
+
+    fn on_finished(&self, _endpoint: &str, _pages: u32, _total: usize) {
+        if let Some(spinner) = self.spinner.lock().unwrap().take() {
+            spinner.finish_and_clear();
+        }
+    }
+}
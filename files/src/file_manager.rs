@@ -2,14 +2,23 @@ use std::{
     collections::HashMap,
     fs::{self, File},
     io::{BufReader, Write},
-    path::Path,
+    path::{Path, PathBuf},
     str,
 };
 
-use genius::model::song::{ArtistSong, ArtistSongWithLyrics};
+use genius::model::{
+    page::AlbumTrack,
+    song::{ArtistSong, ArtistSongWithLyrics},
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Device names Windows reserves, with or without an extension.
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+    "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
 #[derive(Deserialize, Debug, Clone)]
 pub struct FileData {
     pub total: usize,
@@ -38,6 +47,54 @@ pub struct FileDataWithLyrics {
     pub songs: Vec<ArtistSongWithLyrics>,
 }
 
+/// Path of a file about `song` in a directory laid out as `<artist>/<title>.<extension>`.
+pub fn song_file_path(dir: &Path, song: &ArtistSong, extension: &str) -> PathBuf {
+    let mut path = dir.join(file_name(&song.primary_artist.name));
+    path.push(format!("{}.{}", file_name(&song.title), extension));
+    path
+}
+
+/// `name` as a single path component that is valid on every platform.
+///
+/// Path separators and the characters Windows does not allow in file names are replaced by `_`.
+/// Trailing dots and spaces, which Windows drops, are removed, and names that would refer to the parent
+/// directory, such as `..`, or to a device, such as `CON`, are prefixed with `_`.
+fn file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let name = name.trim_end_matches(['.', ' ']);
+    let stem = name.split('.').next().unwrap_or_default().trim_end().to_uppercase();
+    if name.is_empty() || RESERVED_NAMES.contains(&stem.as_str()) {
+        format!("_{}", name)
+    } else {
+        String::from(name)
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct AlbumFileData {
+    pub album_id: Option<u32>,
+    pub name: String,
+    pub artist: Option<String>,
+    pub url: String,
+    pub total: usize,
+    pub tracks: Vec<AlbumTrackWithLyrics>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AlbumTrackWithLyrics {
+    #[serde(flatten)]
+    pub track: AlbumTrack,
+    /// `None` if the track has no song page or it could not be scraped.
+    pub song: Option<ArtistSongWithLyrics>,
+}
+
 pub trait FileManager<T> {
     fn read(path: &Path) -> T;
     fn write(path: &Path, content: Value);
@@ -73,3 +130,28 @@ impl FileManager<FileData> for SongsFileManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_are_valid_on_every_platform() {
+        assert_eq!(file_name("AC/DC"), "AC_DC");
+        assert_eq!(file_name("Who? What: Why*"), "Who_ What_ Why_");
+        assert_eq!(file_name(r#"<"a|b">\c"#), "__a_b___c");
+        assert_eq!(file_name("Line\nbreak"), "Line_break");
+        assert_eq!(file_name("P.O.D."), "P.O.D");
+        assert_eq!(file_name("con"), "_con");
+        assert_eq!(file_name("Nul.txt"), "_Nul.txt");
+        assert_eq!(file_name("Concrete"), "Concrete");
+    }
+
+    #[test]
+    fn file_names_stay_in_their_directory() {
+        assert_eq!(file_name(".."), "_");
+        assert_eq!(file_name("."), "_");
+        assert_eq!(file_name(""), "_");
+        assert_eq!(file_name("../../etc/passwd"), ".._.._etc_passwd");
+    }
+}
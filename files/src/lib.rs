@@ -1 +1,2 @@
-pub mod file_manager;
\ No newline at end of file
+pub mod file_manager;
+pub mod lrc;
@@ -0,0 +1,232 @@
+use std::fmt;
+
+use genius::model::{lyrics::SyncedLine, song::ArtistSongWithLyrics};
+
+/// Header tags of the LRC format. Other bracketed text, such as `[Chorus: Drake]`, is part of the lyrics.
+const HEADER_TAGS: [&str; 10] = ["ti", "ar", "al", "au", "by", "offset", "length", "re", "tool", "ve"];
+
+/// A line of an LRC file. Lines repeated in the song may have several timestamps, and lines without
+/// any are not synced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LrcLine {
+    pub times_ms: Vec<u64>,
+    pub text: String,
+}
+
+/// Lyrics in the LRC format, e.g. `[00:12.34]Today is gonna be the day`.
+///
+/// Only the `ti`, `ar`, `al` and `offset` header tags are kept, other tags are dropped when parsing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lrc {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// Milliseconds subtracted from every timestamp: a positive offset shows the lyrics earlier.
+    pub offset_ms: i64,
+    pub lines: Vec<LrcLine>,
+}
+
+impl Lrc {
+    /// Parses `content` leniently, reading text that follows no timestamp as an unsynced line.
+    pub fn parse(content: &str) -> Self {
+        let mut lrc = Lrc::default();
+        for line in content.lines() {
+            let mut rest = line.trim();
+            let mut times_ms = vec![];
+            let mut header = None;
+            while let Some((tag, after)) = rest.strip_prefix('[').and_then(|tag| tag.split_once(']')) {
+                if let Some(time) = parse_time(tag) {
+                    times_ms.push(time);
+                } else if let Some((key, value)) = tag.split_once(':').filter(|_| times_ms.is_empty()) {
+                    if HEADER_TAGS.contains(&key.trim().to_lowercase().as_str()) {
+                        header = Some((key, value));
+                    }
+                    break;
+                } else {
+                    // Text starting with brackets, such as a `[Chorus]` header.
+                    break;
+                }
+                rest = after;
+            }
+
+            match header {
+                Some((key, value)) => lrc.set_tag(key, value.trim()),
+                None => lrc.lines.push(LrcLine {
+                    times_ms,
+                    text: rest.trim().to_string(),
+                }),
+            }
+        }
+        lrc
+    }
+
+    /// Uses the song's title, primary artist and album as header tags, and its synced lines if it has any.
+    pub fn from_song(song: &ArtistSongWithLyrics) -> Self {
+        let lines = match &song.synced {
+            Some(synced) => synced
+                .iter()
+                .map(|line| LrcLine {
+                    times_ms: vec![line.time_ms],
+                    text: line.text.clone(),
+                })
+                .collect(),
+            None => song
+                .lyrics
+                .lines()
+                .map(|line| LrcLine {
+                    times_ms: vec![],
+                    text: line.to_string(),
+                })
+                .collect(),
+        };
+        let album = song
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.album.as_ref())
+            .map(|album| album.name.clone());
+
+        Lrc {
+            title: Some(song.song.title.clone()),
+            artist: Some(song.song.primary_artist.name.clone()).filter(|artist| !artist.is_empty()),
+            album,
+            offset_ms: 0,
+            lines,
+        }
+    }
+
+    /// One line per timestamp, with the offset applied, ordered by time.
+    pub fn synced(&self) -> Vec<SyncedLine> {
+        let mut synced: Vec<SyncedLine> = self
+            .lines
+            .iter()
+            .flat_map(|line| {
+                line.times_ms.iter().map(|time| SyncedLine {
+                    time_ms: (*time as i64 - self.offset_ms).max(0) as u64,
+                    text: line.text.clone(),
+                })
+            })
+            .collect();
+        synced.sort_by_key(|line| line.time_ms);
+        synced
+    }
+
+    /// The lyrics as plain text, in the order they are sung when the lines are synced.
+    pub fn text(&self) -> String {
+        let text = if self.lines.iter().any(|line| !line.times_ms.is_empty()) {
+            self.synced().into_iter().map(|line| line.text).collect::<Vec<_>>().join("\n")
+        } else {
+            self.lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n")
+        };
+        text.trim().to_string()
+    }
+
+    fn set_tag(&mut self, key: &str, value: &str) {
+        match key.trim().to_lowercase().as_str() {
+            "ti" => self.title = Some(value.to_string()),
+            "ar" => self.artist = Some(value.to_string()),
+            "al" => self.album = Some(value.to_string()),
+            "offset" => self.offset_ms = value.trim_start_matches('+').parse().unwrap_or_default(),
+            _ => {}
+        }
+    }
+}
+
+impl fmt::Display for Lrc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tags = [("ti", &self.title), ("ar", &self.artist), ("al", &self.album)];
+        for (key, value) in tags {
+            if let Some(value) = value {
+                writeln!(f, "[{}:{}]", key, value)?;
+            }
+        }
+        if self.offset_ms != 0 {
+            writeln!(f, "[offset:{:+}]", self.offset_ms)?;
+        }
+        for line in &self.lines {
+            for time in &line.times_ms {
+                write!(f, "[{}]", format_time(*time))?;
+            }
+            writeln!(f, "{}", line.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `mm:ss`, `mm:ss.xx` or `mm:ss.xxx` into milliseconds.
+fn parse_time(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+    let digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    if !digits(minutes) || !digits(seconds) || !(fraction.is_empty() || digits(fraction)) {
+        return None;
+    }
+
+    // `.5` is 500 ms, `.05` is 50 ms and `.005` is 5 ms.
+    let fraction: String = fraction.chars().chain("000".chars()).take(3).collect();
+    Some(minutes.parse::<u64>().ok()? * 60_000 + seconds.parse::<u64>().ok()? * 1000 + fraction.parse::<u64>().ok()?)
+}
+
+/// Formats milliseconds as `mm:ss.xx`.
+fn format_time(time_ms: u64) -> String {
+    format!("{:02}:{:02}.{:02}", time_ms / 60_000, time_ms / 1000 % 60, time_ms % 1000 / 10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(content: &str) -> Lrc {
+        let lrc = Lrc::parse(content);
+        assert_eq!(Lrc::parse(&lrc.to_string()), lrc, "{}", lrc);
+        lrc
+    }
+
+    #[test]
+    fn round_trips_lines_with_several_timestamps() {
+        let lrc = round_trip("[ti:Wonderwall]\n[ar:Oasis]\n[00:01.00][00:10.50]Today is gonna be the day\n[00:05.25]Backbeat");
+        assert_eq!(lrc.title.as_deref(), Some("Wonderwall"));
+        assert_eq!(lrc.lines[0].times_ms, [1000, 10500]);
+
+        let synced: Vec<_> = lrc.synced().into_iter().map(|line| (line.time_ms, line.text)).collect();
+        assert_eq!(
+            synced,
+            [
+                (1000, String::from("Today is gonna be the day")),
+                (5250, String::from("Backbeat")),
+                (10500, String::from("Today is gonna be the day")),
+            ]
+        );
+        assert_eq!(lrc.text(), "Today is gonna be the day\nBackbeat\nToday is gonna be the day");
+    }
+
+    #[test]
+    fn round_trips_the_offset() {
+        let lrc = round_trip("[offset:+500]\n[00:01.00]Early\n[00:00.20]Clamped");
+        assert_eq!(lrc.offset_ms, 500);
+        assert!(lrc.to_string().starts_with("[offset:+500]\n"));
+        let times: Vec<_> = lrc.synced().iter().map(|line| line.time_ms).collect();
+        assert_eq!(times, [0, 500]);
+
+        assert_eq!(round_trip("[offset:-250]\n[00:01.00]Late").synced()[0].time_ms, 1250);
+    }
+
+    #[test]
+    fn keeps_bracketed_lyrics_as_text() {
+        let lrc = round_trip("[Chorus: Drake]\n[00:12.34][Chorus]\n[00:15.00]Line\n[?]");
+        let texts: Vec<_> = lrc.lines.iter().map(|line| line.text.as_str()).collect();
+        assert_eq!(texts, ["[Chorus: Drake]", "[Chorus]", "Line", "[?]"]);
+        assert!(lrc.lines[0].times_ms.is_empty());
+        assert_eq!(lrc.lines[1].times_ms, [12340]);
+        assert!(lrc.title.is_none());
+    }
+
+    #[test]
+    fn parses_timestamp_fractions() {
+        assert_eq!(parse_time("01:02"), Some(62000));
+        assert_eq!(parse_time("01:02.5"), Some(62500));
+        assert_eq!(parse_time("01:02.05"), Some(62050));
+        assert_eq!(parse_time("01:02.005"), Some(62005));
+        assert_eq!(parse_time("ar:Oasis"), None);
+        assert_eq!(format_time(62050), "01:02.05");
+    }
+}
@@ -0,0 +1,38 @@
+use futures::future;
+use log::warn;
+
+use crate::{
+    genius::{Genius, Result, TextFormat},
+    model::album::Album,
+    resolver::normalize,
+};
+
+/// Number of songs by the artist whose album is looked up by [`Genius::find_album`].
+const ALBUM_LOOKUPS: usize = 5;
+
+impl Genius {
+    /// Finds the album titled `title` by `artist`, as the API has no album search.
+    ///
+    /// The albums of the search hits by `artist` are looked up, and the first one whose name
+    /// contains `title` (ignoring case and punctuation) is returned.
+    pub async fn find_album(&self, artist: &str, title: &str) -> Result<Option<Album>> {
+        let hits = self.search(&format!("{} {}", artist, title)).await?;
+        let (artist, title) = (normalize(artist), normalize(title));
+        let songs: Vec<_> = hits
+            .iter()
+            .map(|hit| &hit.result)
+            .filter(|song| normalize(&song.primary_artist.name) == artist)
+            .take(ALBUM_LOOKUPS)
+            .collect();
+
+        let details = future::join_all(songs.iter().map(|song| self.song_detail(song.id, TextFormat::Plain))).await;
+        let mut albums = songs.iter().zip(details).filter_map(|(song, detail)| match detail {
+            Ok(detail) => detail.album,
+            Err(err) => {
+                warn!("Could not look up song `{}`: {}", song.id, err);
+                None
+            }
+        });
+        Ok(albums.find(|album| normalize(&album.name).contains(&title)))
+    }
+}
@@ -0,0 +1,481 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use rand::{distributions::Alphanumeric, Rng};
+use reqwest::{Client, Url};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+use crate::constants::{OAUTH_AUTHORIZE_URL, OAUTH_TOKEN_URL};
+
+const CREDENTIALS_DIR: &str = "lyri";
+const CREDENTIALS_FILE: &str = "credentials.json";
+const STATE_LENGTH: usize = 24;
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+
+/// Client of the Genius OAuth2 authorization-code flow.
+///
+/// https://docs.genius.com/#/authentication-h1
+pub struct OAuthClient {
+    client_id: String,
+    client_secret: String,
+    redirect_uri: String,
+    authorize_url: String,
+    token_url: String,
+    reqwest: Client,
+}
+
+impl OAuthClient {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>, redirect_uri: impl Into<String>) -> Self {
+        Self {
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: redirect_uri.into(),
+            authorize_url: String::from(OAUTH_AUTHORIZE_URL),
+            token_url: String::from(OAUTH_TOKEN_URL),
+            reqwest: Client::new(),
+        }
+    }
+
+    /// Overrides the authorization page, e.g. to point at a local stand-in server.
+    pub fn authorize_url(mut self, authorize_url: impl Into<String>) -> Self {
+        self.authorize_url = authorize_url.into();
+        self
+    }
+
+    /// Overrides the token endpoint, e.g. to point at a local stand-in server.
+    pub fn token_url(mut self, token_url: impl Into<String>) -> Self {
+        self.token_url = token_url.into();
+        self
+    }
+
+    /// URL the user has to visit to grant access. `state` is echoed back to the redirect URI.
+    pub fn authorization_url(&self, scopes: &[String], state: &str) -> Result<Url, AuthError> {
+        Url::parse_with_params(
+            &self.authorize_url,
+            &[
+                ("client_id", self.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("scope", scopes.join(" ").as_str()),
+                ("state", state),
+                ("response_type", "code"),
+            ],
+        )
+        .map_err(|err| AuthError::InvalidUrl(err.to_string()))
+    }
+
+    /// Exchanges the code received on the redirect URI for an access token.
+    pub async fn exchange_code(&self, code: &str) -> Result<Credentials, AuthError> {
+        let response = self
+            .reqwest
+            .post(&self.token_url)
+            .form(&[
+                ("code", code),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+                ("response_type", "code"),
+                ("grant_type", "authorization_code"),
+            ])
+            .send()
+            .await?;
+
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            let message = serde_json::from_str::<TokenErrorResponse>(&body)
+                .ok()
+                .and_then(|err| err.error_description.or(err.error))
+                .unwrap_or(body);
+            return Err(AuthError::TokenExchange {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        serde_json::from_str::<Credentials>(&body).map_err(AuthError::Decode)
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Random value used to bind the redirect to the authorization request.
+pub fn generate_state() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(STATE_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// A minimal HTTP listener on the loopback interface that receives the OAuth2 redirect.
+pub struct RedirectListener {
+    listener: TcpListener,
+}
+
+impl RedirectListener {
+    /// Binds to `127.0.0.1:port`. Use port `0` to let the OS pick a free one.
+    pub async fn bind(port: u16) -> Result<Self, AuthError> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+        Ok(Self { listener })
+    }
+
+    /// Redirect URI to register for the Genius API client.
+    pub fn redirect_uri(&self) -> Result<String, AuthError> {
+        let address = self.listener.local_addr()?;
+        Ok(format!("http://{}/callback", address))
+    }
+
+    /// Waits for the browser to be redirected back and returns the authorization code.
+    ///
+    /// Requests that do not carry a `code` or an `error` parameter (e.g. `/favicon.ico`) are ignored.
+    pub async fn wait_for_code(&self, expected_state: &str) -> Result<String, AuthError> {
+        loop {
+            let (mut stream, _) = self.listener.accept().await?;
+            let request_line = read_request_line(&mut stream).await?;
+            debug!("Redirect listener received `{}`", request_line);
+
+            let params = match parse_callback(&request_line) {
+                Some(params) => params,
+                None => {
+                    respond(&mut stream, "404 Not Found", "Not found").await?;
+                    continue;
+                }
+            };
+            let result = match (params.code, params.error) {
+                (_, Some(error)) => Err(AuthError::Denied(error)),
+                (Some(_), None) if params.state.as_deref() != Some(expected_state) => Err(AuthError::StateMismatch),
+                (Some(code), None) => Ok(code),
+                (None, None) => {
+                    respond(&mut stream, "404 Not Found", "Not found").await?;
+                    continue;
+                }
+            };
+
+            let message = match &result {
+                Ok(_) => "Authorization complete. You can close this window and return to the terminal.",
+                Err(_) => "Authorization failed. Check the terminal for details.",
+            };
+            respond(&mut stream, "200 OK", message).await?;
+            break result;
+        }
+    }
+}
+
+async fn read_request_line(stream: &mut tokio::net::TcpStream) -> Result<String, AuthError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buffer.windows(4).any(|window| window == b"\r\n\r\n") && buffer.len() < MAX_REQUEST_SIZE {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&buffer);
+    Ok(request.lines().next().unwrap_or_default().to_string())
+}
+
+async fn respond(stream: &mut tokio::net::TcpStream, status: &str, message: &str) -> Result<(), AuthError> {
+    let body = format!("<!DOCTYPE html><html><body><p>{}</p></body></html>", message);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+struct CallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// Parses the query of a request line such as `GET /callback?code=abc&state=xyz HTTP/1.1`.
+fn parse_callback(request_line: &str) -> Option<CallbackParams> {
+    let target = request_line.split_whitespace().nth(1)?;
+    let url = Url::parse(&format!("http://localhost{}", target)).ok()?;
+    if url.path() != "/callback" {
+        return None;
+    }
+
+    let mut params = CallbackParams {
+        code: None,
+        state: None,
+        error: None,
+    };
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "code" => params.code = Some(value.into_owned()),
+            "state" => params.state = Some(value.into_owned()),
+            "error" => params.error = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+    Some(params)
+}
+
+/// A user access token obtained through the OAuth2 flow.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Credentials {
+    pub access_token: String,
+    pub token_type: Option<String>,
+    pub scope: Option<String>,
+}
+
+/// Per-user file holding [`Credentials`], by default `$XDG_CONFIG_HOME/lyri/credentials.json`
+/// (or `~/.config/lyri/credentials.json`).
+pub struct CredentialsStore {
+    path: PathBuf,
+}
+
+impl CredentialsStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store at the default location, if a configuration directory can be determined.
+    pub fn user_default() -> Option<Self> {
+        config_dir().map(|dir| Self::new(dir.join(CREDENTIALS_DIR).join(CREDENTIALS_FILE)))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns `None` if no credentials have been stored yet.
+    pub fn load(&self) -> Result<Option<Credentials>, AuthError> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str(&content).map(Some).map_err(AuthError::Decode),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes the credentials to a file only the current user can read. Missing directories are created
+    /// so that only the current user can list them.
+    pub fn save(&self, credentials: &Credentials) -> Result<(), AuthError> {
+        if let Some(parent) = self.path.parent() {
+            create_private_dir(parent)?;
+        }
+        let content = serde_json::to_string_pretty(credentials).map_err(AuthError::Decode)?;
+        let mut file = create_private_file(&self.path)?;
+        // The mode only applies to new files: tighten the permissions of an existing one before writing the token.
+        restrict_permissions(&self.path)?;
+        file.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Removes the stored credentials. Returns `false` if there were none.
+    pub fn clear(&self) -> Result<bool, AuthError> {
+        match fs::remove_file(&self.path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn create_private_file(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+    fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &Path) -> io::Result<fs::File> {
+    fs::OpenOptions::new().write(true).create(true).truncate(true).open(path)
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    /// The user declined the authorization request.
+    Denied(String),
+    /// The redirect carried a `state` that does not match the one sent to Genius.
+    StateMismatch,
+    InvalidUrl(String),
+    TokenExchange { status: u16, message: String },
+    Decode(serde_json::Error),
+    Http(reqwest::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthError::Denied(reason) => write!(f, "Authorization was denied: {}", reason),
+            AuthError::StateMismatch => write!(f, "Authorization response does not match the request"),
+            AuthError::InvalidUrl(err) => write!(f, "Invalid authorization URL: {}", err),
+            AuthError::TokenExchange { status, message } => {
+                write!(f, "Could not exchange the authorization code ({}): {}", status, message)
+            }
+            AuthError::Decode(err) => write!(f, "Could not decode credentials: {}", err),
+            AuthError::Http(err) => write!(f, "Transport error: {}", err),
+            AuthError::Io(err) => write!(f, "I/O error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AuthError::Decode(err) => Some(err),
+            AuthError::Http(err) => Some(err),
+            AuthError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for AuthError {
+    fn from(err: reqwest::Error) -> Self {
+        AuthError::Http(err)
+    }
+}
+
+impl From<io::Error> for AuthError {
+    fn from(err: io::Error) -> Self {
+        AuthError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers a single token request with `response` and returns the form it was sent.
+    async fn serve_token(listener: TcpListener, response: &'static str) -> String {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 1024];
+        let body = loop {
+            let read = stream.read(&mut chunk).await.unwrap();
+            buffer.extend_from_slice(&chunk[..read]);
+            let request = String::from_utf8_lossy(&buffer).into_owned();
+            if let Some((head, body)) = request.split_once("\r\n\r\n") {
+                let length: usize = head
+                    .lines()
+                    .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|l| l.trim().to_string()))
+                    .and_then(|length| length.parse().ok())
+                    .unwrap_or_default();
+                if body.len() >= length || read == 0 {
+                    break body.to_string();
+                }
+            }
+        };
+        let reply = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            response.len(),
+            response
+        );
+        stream.write_all(reply.as_bytes()).await.unwrap();
+        body
+    }
+
+    #[tokio::test]
+    async fn logs_in_against_a_stand_in_token_server() {
+        let token_server = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let token_url = format!("http://{}/oauth/token", token_server.local_addr().unwrap());
+        let token_request = tokio::spawn(serve_token(
+            token_server,
+            r#"{"access_token":"user-token","token_type":"bearer","scope":"me"}"#,
+        ));
+
+        let listener = RedirectListener::bind(0).await.unwrap();
+        let redirect_uri = listener.redirect_uri().unwrap();
+        let oauth = OAuthClient::new("client-id", "client-secret", &redirect_uri).token_url(token_url);
+        let state = generate_state();
+
+        // Plays the browser coming back from the authorization page.
+        let callback = format!("{}?code=the-code&state={}", redirect_uri, state);
+        let browser = tokio::spawn(async move { reqwest::get(callback).await.unwrap().status() });
+
+        let code = listener.wait_for_code(&state).await.unwrap();
+        assert_eq!(code, "the-code");
+        assert!(browser.await.unwrap().is_success());
+
+        let credentials = oauth.exchange_code(&code).await.unwrap();
+        assert_eq!(credentials.access_token, "user-token");
+        let form = token_request.await.unwrap();
+        assert!(form.contains("code=the-code"), "{}", form);
+        assert!(form.contains("grant_type=authorization_code"), "{}", form);
+        assert!(form.contains("client_secret=client-secret"), "{}", form);
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = CredentialsStore::new(dir.path().join(CREDENTIALS_DIR).join(CREDENTIALS_FILE));
+        store.save(&credentials).unwrap();
+        assert_eq!(store.load().unwrap().unwrap().access_token, "user-token");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(store.path()), 0o600);
+            assert_eq!(mode(store.path().parent().unwrap()), 0o700);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_tightens_the_permissions_of_an_existing_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CREDENTIALS_FILE);
+        fs::write(&path, "{}").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let credentials = Credentials {
+            access_token: String::from("token"),
+            token_type: None,
+            scope: None,
+        };
+        CredentialsStore::new(&path).save(&credentials).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
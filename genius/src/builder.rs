@@ -0,0 +1,242 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use net::{cache::HttpCache, client::HttpClient, rate_limit::RateLimiter, transport::HttpTransport};
+use reqwest::Client;
+
+use crate::{
+    auth::{AuthError, CredentialsStore},
+    constants::{BASE_URL, GENIUS_ACCESS_TOKEN_ENV_VAR},
+    genius::Genius,
+    progress::{NoopObserver, ProgressObserver},
+    retry::RetryPolicy,
+};
+
+/// Configures and creates a [`Genius`] client.
+///
+/// Unless set explicitly, the access token is read from the `GENIUS_ACCESS_TOKEN`
+/// environment variable (or the `.env` file), then from the credentials stored by the OAuth2 flow.
+/// The base URL defaults to the public Genius API.
+#[derive(Default)]
+pub struct GeniusBuilder {
+    token: Option<String>,
+    base_url: Option<String>,
+    client: Option<Client>,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    retry_policy: Option<RetryPolicy>,
+    token_from_env: Option<bool>,
+    credentials_store: Option<CredentialsStore>,
+    observer: Option<Arc<dyn ProgressObserver>>,
+    cache: Option<Arc<HttpCache>>,
+    offline: bool,
+    transport: Option<Arc<dyn HttpTransport>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl GeniusBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Access token used for the `Authorization` header.
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Base URL of the API, e.g. `http://127.0.0.1:8080` for a local stand-in server.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Uses an existing `reqwest` client. When set, `user_agent` and `timeout` are ignored.
+    /// Ignored when a `transport` is set.
+    pub fn client(mut self, client: Client) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Retry policy applied to every request. Defaults to [`RetryPolicy::default`].
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Whether to read the access token from the `GENIUS_ACCESS_TOKEN` environment variable and the `.env` file
+    /// when none is set explicitly. Defaults to `true`.
+    pub fn token_from_env(mut self, token_from_env: bool) -> Self {
+        self.token_from_env = Some(token_from_env);
+        self
+    }
+
+    /// Where to look for stored credentials. Defaults to [`CredentialsStore::user_default`].
+    pub fn credentials_store(mut self, credentials_store: CredentialsStore) -> Self {
+        self.credentials_store = Some(credentials_store);
+        self
+    }
+
+    /// Receives progress events such as fetched pages and retries. Defaults to [`NoopObserver`].
+    pub fn observer(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Caches successful GET responses on disk. The cache can be shared with the scraper.
+    pub fn cache(mut self, cache: Arc<HttpCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Serves requests from the cache only and fails when a response is not cached.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Sends requests through a custom transport, e.g. one that records or replays fixtures.
+    /// When set, `client`, `user_agent` and `timeout` are ignored.
+    pub fn transport(mut self, transport: Arc<dyn HttpTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Spaces out requests sent to Genius, including concurrently fetched pages.
+    /// The limiter can be shared with the scraper.
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    pub fn build(self) -> Result<Genius, BuildError> {
+        let env_token = if self.token_from_env.unwrap_or(true) {
+            dotenv::var(GENIUS_ACCESS_TOKEN_ENV_VAR).ok()
+        } else {
+            None
+        };
+        let auth_token = match self.token.or(env_token) {
+            Some(token) => token,
+            None => self
+                .credentials_store
+                .or_else(CredentialsStore::user_default)
+                .map(|store| store.load())
+                .transpose()
+                .map_err(BuildError::Credentials)?
+                .flatten()
+                .map(|credentials| credentials.access_token)
+                .ok_or(BuildError::MissingToken)?,
+        };
+        let base_url = self
+            .base_url
+            .unwrap_or_else(|| String::from(BASE_URL))
+            .trim_end_matches('/')
+            .to_string();
+        let http = match (self.transport, self.client) {
+            (Some(transport), _) => HttpClient::with_transport(transport),
+            (None, Some(client)) => HttpClient::new(client),
+            (None, None) => {
+                let mut builder = Client::builder();
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                HttpClient::new(builder.build().map_err(BuildError::Client)?)
+            }
+        };
+
+        let mut http = http.offline(self.offline);
+        if let Some(cache) = self.cache {
+            http = http.cache(cache);
+        }
+        if let Some(rate_limiter) = self.rate_limiter {
+            http = http.rate_limiter(rate_limiter);
+        }
+
+        Ok(Genius {
+            http,
+            auth_token,
+            base_url,
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            observer: self.observer.unwrap_or_else(|| Arc::new(NoopObserver)),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum BuildError {
+    MissingToken,
+    /// The stored credentials could not be read, e.g. because the file is corrupt.
+    Credentials(AuthError),
+    Client(reqwest::Error),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingToken => write!(
+                f,
+                "Could not find environment variable `{}`. Make sure it is declared in the `.env` file or run `lyri auth login`.",
+                GENIUS_ACCESS_TOKEN_ENV_VAR
+            ),
+            BuildError::Credentials(err) => write!(f, "Could not load the stored credentials: {}", err),
+            BuildError::Client(err) => write!(f, "Could not build HTTP client: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::MissingToken => None,
+            BuildError::Credentials(err) => Some(err),
+            BuildError::Client(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn builder_with_credentials(content: Option<&str>) -> (GeniusBuilder, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+        let builder = GeniusBuilder::new()
+            .token_from_env(false)
+            .credentials_store(CredentialsStore::new(path));
+        (builder, dir)
+    }
+
+    #[test]
+    fn build_surfaces_unreadable_credentials() {
+        let (builder, _dir) = builder_with_credentials(Some("not json"));
+        assert!(matches!(builder.build(), Err(BuildError::Credentials(AuthError::Decode(_)))));
+    }
+
+    #[test]
+    fn build_reads_stored_credentials() {
+        let credentials = r#"{"access_token":"stored","token_type":null,"scope":null}"#;
+        let (builder, _dir) = builder_with_credentials(Some(credentials));
+        assert_eq!(builder.build().unwrap().auth_token, "stored");
+
+        let (builder, _dir) = builder_with_credentials(None);
+        assert!(matches!(builder.build(), Err(BuildError::MissingToken)));
+    }
+}
@@ -1,4 +1,6 @@
 pub const BASE_URL: &str = "https://api.genius.com";
+pub const OAUTH_AUTHORIZE_URL: &str = "https://api.genius.com/oauth/authorize";
+pub const OAUTH_TOKEN_URL: &str = "https://api.genius.com/oauth/token";
 pub const GENIUS_ACCESS_TOKEN_ENV_VAR: &str = "GENIUS_ACCESS_TOKEN";
 pub const PAGE_INDEX_PARAM: &str = "page";
 pub const PER_PAGE_PARAM: &str = "per_page";
@@ -6,3 +8,10 @@ pub const PER_PAGE_PARAM_LIMIT: &str = "50";
 pub const SORT_PARAM: &str = "sort";
 pub const SORT_PARAM_POPULARITY: &str = "popularity";
 pub const SORT_PARAM_TITLE: &str = "title";
+pub const TEXT_FORMAT_PARAM: &str = "text_format";
+pub const SONG_ID_PARAM: &str = "song_id";
+pub const WEB_PAGE_ID_PARAM: &str = "web_page_id";
+pub const CREATED_BY_ID_PARAM: &str = "created_by_id";
+pub const RAW_ANNOTATABLE_URL_PARAM: &str = "raw_annotatable_url";
+pub const CANONICAL_URL_PARAM: &str = "canonical_url";
+pub const OG_URL_PARAM: &str = "og_url";
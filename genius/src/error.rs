@@ -0,0 +1,136 @@
+use std::{fmt, time::Duration};
+
+use net::client::NetError;
+use reqwest::StatusCode;
+
+use crate::model::responses::ErrorEnvelope;
+
+const BODY_SNIPPET_LENGTH: usize = 200;
+
+#[derive(Debug)]
+pub enum GeniusError {
+    /// The access token is missing, invalid or lacks the required scope (401).
+    Unauthorized { message: String },
+    /// The requested resource does not exist (404).
+    NotFound { message: String },
+    /// Too many requests (429). `retry_after` is taken from the `Retry-After` header when present.
+    RateLimited { retry_after: Option<Duration> },
+    /// Genius failed to process the request (5xx).
+    Server { status: u16, message: String },
+    /// Any other unsuccessful status, e.g. 400 or 403.
+    Api { status: u16, message: String },
+    /// The payload does not match the expected schema. `body` holds the beginning of the payload.
+    Decode { source: serde_json::Error, body: String },
+    /// The payload is valid but does not contain the requested item.
+    EmptyResponse,
+    /// The request is incomplete and has not been sent, e.g. a web page lookup without any URL.
+    InvalidRequest { message: String },
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// The response could not be served without the network, e.g. in offline mode or from fixtures.
+    Net(NetError),
+    /// The request kept failing after `attempts` attempts. `source` is the last failure.
+    RetriesExhausted { attempts: u32, source: Box<GeniusError> },
+}
+
+impl GeniusError {
+    pub(crate) fn from_status(status: u16, retry_after: Option<Duration>, body: &str) -> Self {
+        let message = serde_json::from_str::<ErrorEnvelope>(body)
+            .ok()
+            .and_then(|envelope| envelope.message())
+            .or_else(|| {
+                StatusCode::from_u16(status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .map(String::from)
+            })
+            .unwrap_or_default();
+
+        Self::from_parts(status, message, retry_after)
+    }
+
+    pub(crate) fn from_parts(status: u16, message: String, retry_after: Option<Duration>) -> Self {
+        match status {
+            401 => GeniusError::Unauthorized { message },
+            404 => GeniusError::NotFound { message },
+            429 => GeniusError::RateLimited { retry_after },
+            500..=599 => GeniusError::Server { status, message },
+            _ => GeniusError::Api { status, message },
+        }
+    }
+
+    pub(crate) fn decode(source: serde_json::Error, body: &str) -> Self {
+        GeniusError::Decode {
+            source,
+            body: body.chars().take(BODY_SNIPPET_LENGTH).collect(),
+        }
+    }
+
+    /// HTTP status associated with the error, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            GeniusError::Unauthorized { .. } => Some(401),
+            GeniusError::NotFound { .. } => Some(404),
+            GeniusError::RateLimited { .. } => Some(429),
+            GeniusError::Server { status, .. } | GeniusError::Api { status, .. } => Some(*status),
+            GeniusError::Transport(err) => err.status().map(|status| status.as_u16()),
+            GeniusError::Decode { .. }
+            | GeniusError::EmptyResponse
+            | GeniusError::InvalidRequest { .. }
+            | GeniusError::Net(_) => None,
+            GeniusError::RetriesExhausted { source, .. } => source.status(),
+        }
+    }
+}
+
+impl fmt::Display for GeniusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeniusError::Unauthorized { message } => write!(f, "Unauthorized: {}", message),
+            GeniusError::NotFound { message } => write!(f, "Not found: {}", message),
+            GeniusError::RateLimited { retry_after: Some(delay) } => {
+                write!(f, "Rate limited, retry after {}s", delay.as_secs())
+            }
+            GeniusError::RateLimited { retry_after: None } => write!(f, "Rate limited"),
+            GeniusError::Server { status, message } => write!(f, "Server error ({}): {}", status, message),
+            GeniusError::Api { status, message } => write!(f, "Request failed ({}): {}", status, message),
+            GeniusError::Decode { source, body } => {
+                write!(f, "Could not decode response: {}. Body: `{}`", source, body)
+            }
+            GeniusError::EmptyResponse => write!(f, "No item has been returned"),
+            GeniusError::InvalidRequest { message } => write!(f, "Invalid request: {}", message),
+            GeniusError::Transport(err) => write!(f, "Transport error: {}", err),
+            GeniusError::Net(err) => write!(f, "{}", err),
+            GeniusError::RetriesExhausted { attempts, source } => {
+                write!(f, "Giving up after {} attempts: {}", attempts, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeniusError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GeniusError::Decode { source, .. } => Some(source),
+            GeniusError::Transport(err) => Some(err),
+            GeniusError::Net(err) => Some(err),
+            GeniusError::RetriesExhausted { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for GeniusError {
+    fn from(err: reqwest::Error) -> Self {
+        GeniusError::Transport(err)
+    }
+}
+
+impl From<NetError> for GeniusError {
+    fn from(err: NetError) -> Self {
+        match err {
+            NetError::Transport(err) => GeniusError::Transport(err),
+            err => GeniusError::Net(err),
+        }
+    }
+}
@@ -1,156 +1,424 @@
-use log::{error};
-use serde::{de::DeserializeOwned};
+use std::{sync::Arc, time::Duration};
 
-use crate::{constants::{GENIUS_ACCESS_TOKEN_ENV_VAR, BASE_URL, PER_PAGE_PARAM, PAGE_INDEX_PARAM, PER_PAGE_PARAM_LIMIT, SORT_PARAM_POPULARITY, SORT_PARAM, SORT_PARAM_TITLE}, model::responses::WrappedResponse};
+use futures::{Stream, TryStreamExt};
+use log::{debug, error, warn};
+use net::client::{HttpClient, HttpRequest};
+use reqwest::{header::RETRY_AFTER, Method};
+use serde::de::DeserializeOwned;
 
-use {
-    crate::model::{
+use crate::{
+    builder::GeniusBuilder,
+    constants::{
+        CANONICAL_URL_PARAM, CREATED_BY_ID_PARAM, OG_URL_PARAM, PAGE_INDEX_PARAM, RAW_ANNOTATABLE_URL_PARAM,
+        SONG_ID_PARAM, SORT_PARAM, SORT_PARAM_POPULARITY, SORT_PARAM_TITLE, TEXT_FORMAT_PARAM, WEB_PAGE_ID_PARAM,
+    },
+    error::GeniusError,
+    model::{
+        annotation::{Annotation, Vote},
         artist::Artist,
         hit::Hit,
-        responses::{ArtistResponse, ArtistSongsResponse, SearchResponse, SongResponse},
-        song::ArtistSong,
+        referent::Referent,
+        responses::{
+            AccountResponse, AnnotationResponse, ArtistResponse, ArtistSongsResponse, ReferentsResponse,
+            SearchResponse, SongDetailResponse, SongResponse, WebPageResponse, WrappedResponse,
+        },
+        song::{ArtistSong, SongDetail},
+        user::User,
+        web_page::WebPage,
     },
-    reqwest::{Client, Error},
+    pagination::{Page, PageOptions, Paginator},
+    progress::ProgressObserver,
+    retry::RetryPolicy,
 };
 
+pub type Result<T> = std::result::Result<T, GeniusError>;
+
 pub struct Genius {
-    reqwest: Client,
-    auth_token: String,
+    pub(crate) http: HttpClient,
+    pub(crate) auth_token: String,
+    pub(crate) base_url: String,
+    pub(crate) retry_policy: RetryPolicy,
+    pub(crate) observer: Arc<dyn ProgressObserver>,
 }
 
 impl Genius {
+    /// Creates a client with the default configuration.
+    ///
+    /// Panics if the access token cannot be found. Use [`Genius::builder`] to handle this case.
+    #[allow(clippy::new_without_default)] // `Default` is not expected to panic.
     pub fn new() -> Self {
-        Self {
-            reqwest: Client::new(),
-            auth_token: dotenv::var(GENIUS_ACCESS_TOKEN_ENV_VAR).unwrap_or_else(|_| 
-                panic!( "Could not find environment variable `{}`. Make sure it is declared in the `.env` file.", GENIUS_ACCESS_TOKEN_ENV_VAR),
-            ),
-        }
+        Self::builder().build().unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn builder() -> GeniusBuilder {
+        GeniusBuilder::new()
     }
 
     /// https://docs.genius.com/#/search-h2
-    pub async fn search(&self, q: &str) -> Result<Vec<Hit>, Error> {
-        let response = self
-            .reqwest
-            .get(format!("{}/search", BASE_URL))
-            .query(&[("q", q)])
-            .bearer_auth(&self.auth_token)
-            .send()
-            .await?;
+    pub async fn search(&self, q: &str) -> Result<Vec<Hit>> {
+        let query = [("q", String::from(q))];
+        self.handle_vector_response::<SearchResponse>("/search", &query).await
+    }
 
-       self.handle_vector_response::<SearchResponse>(response).await 
+    /// https://docs.genius.com/#search-h2
+    ///
+    /// Same as [`Genius::search`], but returns the given page of hits, starting at 1.
+    pub async fn search_page(&self, q: &str, page: u32) -> Result<Vec<Hit>> {
+        let query = [("q", String::from(q)), (PAGE_INDEX_PARAM, page.to_string())];
+        self.handle_vector_response::<SearchResponse>("/search", &query).await
     }
 
     /// https://docs.genius.com/#songs-h2
-    pub async fn songs(&self, id: u32) -> Result<ArtistSong, Error> {
-        let response = self
-            .reqwest
-            .get(format!("{}/songs/{}", BASE_URL, id))
-            .bearer_auth(&self.auth_token)
-            .send()
-            .await?;
+    pub async fn songs(&self, id: u32) -> Result<ArtistSong> {
+        self.handle_response::<SongResponse>(&format!("/songs/{}", id), &[]).await
+    }
 
-        self.handle_response::<SongResponse>(response).await
+    /// https://docs.genius.com/#songs-h2
+    ///
+    /// Unlike [`Genius::songs`], keeps the album, credits, media, relationships, description and stats.
+    pub async fn song_detail(&self, id: u32, text_format: TextFormat) -> Result<SongDetail> {
+        let query = [(TEXT_FORMAT_PARAM, String::from(text_format.as_str()))];
+        self.handle_response::<SongDetailResponse>(&format!("/songs/{}", id), &query)
+            .await
     }
 
     /// https://docs.genius.com/#artists-h2
-    pub async fn artists(&self, id: u32) -> Result<Artist, Error> {
-        let response = self
-            .reqwest
-            .get(format!("{}/artists/{}", BASE_URL, id))
-            .bearer_auth(&self.auth_token)
-            .send()
-            .await?;
-
-        self.handle_response::<ArtistResponse>(response).await        
+    pub async fn artists(&self, id: u32) -> Result<Artist> {
+        self.handle_response::<ArtistResponse>(&format!("/artists/{}", id), &[]).await
     }
 
     /// https://docs.genius.com/#artists-h2
-    pub async fn artists_songs(&self, artist_id: u32, options: ArtistSongsOptions) -> Result<Vec<ArtistSong>, Error> {
-        let spinner = cli::progress::fetch_progress_bar();
-        let mut page: u16 = 1;
-        let mut all_songs: Vec<ArtistSong> = vec![];
-        let mut query_params = vec![
-            (PAGE_INDEX_PARAM, page.to_string()), 
-            (PER_PAGE_PARAM, String::from(PER_PAGE_PARAM_LIMIT))];
-            
+    ///
+    /// Fetches every page of the artist's songs. Use [`Genius::artists_songs_stream`] to stop early.
+    pub async fn artists_songs(&self, artist_id: u32, options: ArtistSongsOptions) -> Result<Vec<ArtistSong>> {
+        self.artists_songs_stream(artist_id, options)
+            .try_collect()
+            .await
+    }
+
+    /// Streams the artist's songs, following `next_page` and honoring `max_items`/`max_pages`.
+    pub fn artists_songs_stream(
+        &self,
+        artist_id: u32,
+        options: ArtistSongsOptions,
+    ) -> impl Stream<Item = Result<ArtistSong>> + '_ {
+        self.artists_songs_paginator(artist_id, options)
+            .items::<ArtistSongsResponse>()
+    }
+
+    /// Same as [`Genius::artists_songs_stream`], but yields whole pages.
+    pub fn artists_songs_pages(
+        &self,
+        artist_id: u32,
+        options: ArtistSongsOptions,
+    ) -> impl Stream<Item = Result<Vec<ArtistSong>>> + '_ {
+        self.artists_songs_paginator(artist_id, options)
+            .pages::<ArtistSongsResponse>()
+    }
+
+    fn artists_songs_paginator(&self, artist_id: u32, options: ArtistSongsOptions) -> Paginator<'_> {
+        let mut query_params = vec![];
         if let Some(sort) = options.sort {
             match sort {
                 SongsSort::Popularity => query_params.push((SORT_PARAM, String::from(SORT_PARAM_POPULARITY))),
-                SongsSort::Title => query_params.push((SORT_PARAM, String::from(SORT_PARAM_TITLE)))
+                SongsSort::Title => query_params.push((SORT_PARAM, String::from(SORT_PARAM_TITLE))),
             }
         }
 
-        loop {
-            query_params[0].1 = page.to_string();
-            let response = self
-                .reqwest
-                .get(format!("{}/artists/{}/songs", BASE_URL, artist_id))
-                .query(&query_params)
-                .bearer_auth(&self.auth_token)
-                .send()
-                .await?;
-
-            let current_page_songs = self.handle_vector_response::<ArtistSongsResponse>(response).await?;
-            
-            if current_page_songs.is_empty() {
-                spinner.finish_and_clear();
-                break Ok(all_songs);
-            }
-            else {
-                all_songs.extend(current_page_songs);
-                page += 1;
-            }
+        Paginator::new(
+            self,
+            format!("/artists/{}/songs", artist_id),
+            query_params,
+            PageOptions {
+                max_items: options.max_items,
+                max_pages: options.max_pages,
+                concurrency: options.concurrency,
+            },
+        )
+    }
+
+    /// https://docs.genius.com/#referents-h2
+    ///
+    /// Fetches every page of referents for a song or web page, with their annotations.
+    pub async fn referents(&self, target: ReferentsTarget, options: ReferentsOptions) -> Result<Vec<Referent>> {
+        self.referents_stream(target, options).try_collect().await
+    }
+
+    /// Streams the referents of a song or web page, honoring `max_items`/`max_pages`.
+    pub fn referents_stream(
+        &self,
+        target: ReferentsTarget,
+        options: ReferentsOptions,
+    ) -> impl Stream<Item = Result<Referent>> + '_ {
+        self.referents_paginator(target, options)
+            .items::<ReferentsResponse>()
+    }
+
+    /// Same as [`Genius::referents_stream`], but yields whole pages.
+    pub fn referents_pages(
+        &self,
+        target: ReferentsTarget,
+        options: ReferentsOptions,
+    ) -> impl Stream<Item = Result<Vec<Referent>>> + '_ {
+        self.referents_paginator(target, options)
+            .pages::<ReferentsResponse>()
+    }
+
+    fn referents_paginator(&self, target: ReferentsTarget, options: ReferentsOptions) -> Paginator<'_> {
+        let mut query_params = vec![(TEXT_FORMAT_PARAM, String::from(options.text_format.as_str()))];
+        match target {
+            ReferentsTarget::Song(song_id) => query_params.push((SONG_ID_PARAM, song_id.to_string())),
+            ReferentsTarget::WebPage(web_page_id) => query_params.push((WEB_PAGE_ID_PARAM, web_page_id.to_string())),
         }
+        if let Some(created_by_id) = options.created_by_id {
+            query_params.push((CREATED_BY_ID_PARAM, created_by_id.to_string()));
+        }
+
+        Paginator::new(
+            self,
+            String::from("/referents"),
+            query_params,
+            PageOptions {
+                max_items: options.max_items,
+                max_pages: options.max_pages,
+                ..Default::default()
+            },
+        )
     }
 
-    async fn handle_response<T>(&self, res: reqwest::Response) -> Result<T::Item, Error>
+    /// https://docs.genius.com/#annotations-h2
+    pub async fn annotations(&self, id: u32, text_format: TextFormat) -> Result<Annotation> {
+        let query = [(TEXT_FORMAT_PARAM, String::from(text_format.as_str()))];
+        self.handle_response::<AnnotationResponse>(&format!("/annotations/{}", id), &query)
+            .await
+    }
+
+    /// https://docs.genius.com/#annotations-h2
+    ///
+    /// Votes on an annotation as the authenticated user, or removes the vote when `vote` is `None`.
+    /// Requires a user token with the `vote` scope.
+    pub async fn vote_annotation(&self, id: u32, vote: Option<Vote>, text_format: TextFormat) -> Result<Annotation> {
+        let action = match vote {
+            Some(Vote::Up) => "upvote",
+            Some(Vote::Down) => "downvote",
+            None => "unvote",
+        };
+        let query = [(TEXT_FORMAT_PARAM, String::from(text_format.as_str()))];
+        self.request::<AnnotationResponse>(Method::PUT, &format!("/annotations/{}/{}", id, action), &query, false)
+            .await?
+            .get_item()
+            .ok_or(GeniusError::EmptyResponse)
+    }
+
+    /// https://docs.genius.com/#web_pages-h2
+    ///
+    /// Looks up a web page by any of its URLs. At least one of them must be set.
+    pub async fn web_pages_lookup(&self, lookup: WebPageLookup) -> Result<WebPage> {
+        let query: Vec<(&str, String)> = [
+            (RAW_ANNOTATABLE_URL_PARAM, lookup.raw_annotatable_url),
+            (CANONICAL_URL_PARAM, lookup.canonical_url),
+            (OG_URL_PARAM, lookup.og_url),
+        ]
+        .into_iter()
+        .filter_map(|(param, value)| value.map(|value| (param, value)))
+        .collect();
+        if query.is_empty() {
+            return Err(GeniusError::InvalidRequest {
+                message: String::from("a web page lookup needs at least one URL"),
+            });
+        }
+
+        self.handle_response::<WebPageResponse>("/web_pages/lookup", &query)
+            .await
+    }
+
+    /// https://docs.genius.com/#account-h2
+    ///
+    /// Returns the user the access token belongs to. Requires a user token with the `me` scope.
+    /// The response is never cached.
+    pub async fn account(&self) -> Result<User> {
+        self.request::<AccountResponse>(Method::GET, "/account", &[], false)
+            .await?
+            .get_item()
+            .ok_or(GeniusError::EmptyResponse)
+    }
+
+    async fn handle_response<T>(&self, path: &str, query: &[(&str, String)]) -> Result<T::Item>
     where
         T: DeserializeOwned + ResponseSingleItem,
     {
-        match res.error_for_status() {
-            Ok(res_ok) => {
-                let item_res = res_ok.json::<WrappedResponse<T>>().await?;
-                match item_res.response.get_item() {
-                    Some(item) => Ok(item),
-                    None => panic!("No item has been returned"),
-                }
-            }
-            Err(res_err) => {
-                error!("Bad status code: {:?}", res_err.status());
-                Err(res_err)
-            }
-        }
+        self.get::<T>(path, query)
+            .await?
+            .get_item()
+            .ok_or(GeniusError::EmptyResponse)
     }
 
-    async fn handle_vector_response<T>(&self, res: reqwest::Response) -> Result<Vec<T::Item>, Error>
+    async fn handle_vector_response<T>(&self, path: &str, query: &[(&str, String)]) -> Result<Vec<T::Item>>
     where
         T: DeserializeOwned + ResponseMultipleItems,
     {
-        match res.error_for_status() {
-            Ok(res_ok) => {
-                let items_res = res_ok.json::<WrappedResponse<T>>().await?;
-                match items_res.response.get_items() {
-                    Some(items) => Ok(items),
-                    None => panic!("No items have been returned"),
+        self.get::<T>(path, query)
+            .await?
+            .get_items()
+            .ok_or(GeniusError::EmptyResponse)
+    }
+
+    /// Sends a cacheable GET request to `path` and unwraps the `response` field of the Genius envelope.
+    pub(crate) async fn get<T>(&self, path: &str, query: &[(&str, String)]) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        self.request::<T>(Method::GET, path, query, true).await
+    }
+
+    /// Sends a request to `path` and unwraps the `response` field of the Genius envelope.
+    /// Failed requests are retried according to the client's [`RetryPolicy`].
+    async fn request<T>(&self, method: Method, path: &str, query: &[(&str, String)], cacheable: bool) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let mut attempt = 1;
+        let result = loop {
+            match self.request_once::<T>(method.clone(), path, query, cacheable).await {
+                Ok(response) => break Ok(response),
+                Err(err) if !self.retry_policy.is_retryable(&err) => break Err(err),
+                Err(err) if attempt >= self.retry_policy.max_attempts => {
+                    break if attempt == 1 {
+                        Err(err)
+                    } else {
+                        error!("Request to `{}` failed after {} attempts: {}", path, attempt, err);
+                        Err(GeniusError::RetriesExhausted {
+                            attempts: attempt,
+                            source: Box::new(err),
+                        })
+                    };
+                }
+                Err(err) => {
+                    let delay = self.retry_policy.delay(attempt, &err);
+                    self.observer.on_retry(path, attempt + 1, delay, &err);
+                    warn!(
+                        "Request to `{}` failed: {}. Retrying in {:?} (attempt {}/{})",
+                        path,
+                        err,
+                        delay,
+                        attempt + 1,
+                        self.retry_policy.max_attempts
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
                 }
             }
-            Err(res_err) => {
-                error!("Bad status code: {:?}", res_err.status());
-                Err(res_err)
-            }
+        };
+
+        if let Err(err) = &result {
+            self.observer.on_error(path, err);
+        }
+        result
+    }
+
+    async fn request_once<T>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        cacheable: bool,
+    ) -> Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        let request = HttpRequest::new(method, format!("{}{}", self.base_url, path))
+            .query(query)
+            .bearer_auth(&self.auth_token)
+            .cacheable(cacheable);
+        let response = self.http.send(request).await?;
+
+        let status = response.status;
+        let retry_after = response
+            .header(RETRY_AFTER.as_str())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        let body = response.body;
+
+        if !(200..300).contains(&status) {
+            debug!("Bad status code: {:?}", status);
+            return Err(GeniusError::from_status(status, retry_after, &body));
+        }
+
+        let wrapped = serde_json::from_str::<WrappedResponse<T>>(&body)
+            .map_err(|err| GeniusError::decode(err, &body))?;
+        match wrapped.meta {
+            Some(meta) if meta.status >= 400 => Err(GeniusError::from_parts(
+                meta.status,
+                meta.message.unwrap_or_default(),
+                retry_after,
+            )),
+            _ => wrapped.response.ok_or(GeniusError::EmptyResponse),
         }
     }
-    
 }
 
+#[derive(Default)]
 pub struct ArtistSongsOptions {
-    pub sort: Option<SongsSort>    
+    pub sort: Option<SongsSort>,
+    /// Stops after this many songs have been fetched.
+    pub max_items: Option<usize>,
+    /// Stops after this many pages have been fetched.
+    pub max_pages: Option<u32>,
+    /// Fetches up to this many pages concurrently, in API order. Requests still go through the
+    /// client's rate limiter. Defaults to fetching one page at a time.
+    pub concurrency: Option<usize>,
 }
 
+#[derive(Clone, Copy)]
 pub enum SongsSort {
     Popularity,
-    Title
+    Title,
+}
+
+/// URLs identifying a web page. Genius matches any of the ones that are set.
+#[derive(Default)]
+pub struct WebPageLookup {
+    pub raw_annotatable_url: Option<String>,
+    pub canonical_url: Option<String>,
+    pub og_url: Option<String>,
+}
+
+/// The annotatable whose referents are requested.
+#[derive(Clone, Copy)]
+pub enum ReferentsTarget {
+    Song(u32),
+    WebPage(u32),
+}
+
+#[derive(Default)]
+pub struct ReferentsOptions {
+    /// Only returns referents created by this user.
+    pub created_by_id: Option<u32>,
+    /// Format of the annotation bodies.
+    pub text_format: TextFormat,
+    /// Stops after this many referents have been fetched.
+    pub max_items: Option<usize>,
+    /// Stops after this many pages have been fetched.
+    pub max_pages: Option<u32>,
+}
+
+/// Representation of rich text fields such as descriptions and annotation bodies.
+#[derive(Clone, Copy, Default)]
+pub enum TextFormat {
+    #[default]
+    Plain,
+    Html,
+    Dom,
+}
+
+impl TextFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TextFormat::Plain => "plain",
+            TextFormat::Html => "html",
+            TextFormat::Dom => "dom",
+        }
+    }
 }
 
 trait ResponseMultipleItems {
@@ -165,10 +433,24 @@ impl ResponseMultipleItems for SearchResponse {
     }
 }
 
-impl ResponseMultipleItems for ArtistSongsResponse {
+impl Page for ArtistSongsResponse {
     type Item = ArtistSong;
-    fn get_items(self) -> Option<Vec<Self::Item>> {
-        self.songs
+    fn into_items(self) -> Vec<Self::Item> {
+        self.songs.unwrap_or_default()
+    }
+    fn next_page(&self, _current: u32) -> Option<u32> {
+        self.next_page
+    }
+}
+
+impl Page for ReferentsResponse {
+    type Item = Referent;
+    fn into_items(self) -> Vec<Self::Item> {
+        self.referents.unwrap_or_default()
+    }
+    /// `/referents` does not report the next page, so it is walked until an empty page is returned.
+    fn next_page(&self, current: u32) -> Option<u32> {
+        Some(current + 1)
     }
 }
 
@@ -184,6 +466,34 @@ impl ResponseSingleItem for SongResponse {
     }
 }
 
+impl ResponseSingleItem for SongDetailResponse {
+    type Item = SongDetail;
+    fn get_item(self) -> Option<Self::Item> {
+        self.song
+    }
+}
+
+impl ResponseSingleItem for AnnotationResponse {
+    type Item = Annotation;
+    fn get_item(self) -> Option<Self::Item> {
+        self.annotation
+    }
+}
+
+impl ResponseSingleItem for WebPageResponse {
+    type Item = WebPage;
+    fn get_item(self) -> Option<Self::Item> {
+        self.web_page
+    }
+}
+
+impl ResponseSingleItem for AccountResponse {
+    type Item = User;
+    fn get_item(self) -> Option<Self::Item> {
+        self.user
+    }
+}
+
 impl ResponseSingleItem for ArtistResponse {
     type Item = Artist;
     fn get_item(self) -> Option<Self::Item> {
@@ -191,3 +501,214 @@ impl ResponseSingleItem for ArtistResponse {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use net::{
+        client::NetError,
+        transport::{HttpTransport, RecordingTransport, ReplayTransport},
+    };
+
+    use super::*;
+    use crate::{builder::GeniusBuilder, test_support::Fixtures};
+
+    /// Fixtures of `GET /songs/100337` (also with `text_format=plain` and `html`), of its referents with
+    /// `text_format=dom`, of `GET /annotations/2871517?text_format=html` and of `GET /search?q=portishead all mine`,
+    /// in the format written by `--record`.
+    fn replay_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/replay")
+    }
+
+    fn genius_with(transport: Arc<dyn HttpTransport>) -> Genius {
+        GeniusBuilder::new().token("replay").transport(transport).build().unwrap()
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_responses() {
+        let genius = genius_with(Arc::new(ReplayTransport::new(replay_dir())));
+
+        let song = genius.songs(100337).await.unwrap();
+        assert_eq!(song.title, "All Mine");
+        let hits = genius.search("portishead all mine").await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].result.id, 100337);
+
+        let missing = genius.songs(1).await;
+        assert!(matches!(missing, Err(GeniusError::Net(NetError::MissingFixture { .. }))));
+    }
+
+    #[tokio::test]
+    async fn replays_song_details() {
+        let genius = genius_with(Arc::new(ReplayTransport::new(replay_dir())));
+
+        let song = genius.song_detail(100337, TextFormat::Plain).await.unwrap();
+        assert_eq!(song.song.id, 100337);
+        assert_eq!(song.album.as_ref().map(|album| album.id), Some(11913));
+        let components = song.release_date_components.unwrap();
+        assert_eq!((components.year, components.month, components.day), (Some(1997), Some(9), Some(8)));
+        assert_eq!(song.song_relationships.len(), 3);
+        let sampled_in = &song.song_relationships[1];
+        assert_eq!(sampled_in.relationship_type, "sampled_in");
+        assert_eq!(sampled_in.songs[0].id, 2402451);
+        assert!(song.song_relationships[0].songs.is_empty());
+        assert_eq!(song.stats.unwrap().pageviews, Some(216543));
+        assert_eq!(song.media[0].start, Some(0));
+        assert_eq!(song.media[1].start, None);
+        assert_eq!(song.custom_performances[0].label, "Label");
+        assert_eq!(song.writer_artists.len(), 3);
+        assert!(song.featured_artists.is_empty());
+        let description = song.description.unwrap();
+        assert!(description.plain.unwrap().starts_with("“All Mine”"));
+        assert!(description.html.is_none());
+
+        let song = genius.song_detail(100337, TextFormat::Html).await.unwrap();
+        let description = song.description.unwrap();
+        assert!(description.plain.is_none());
+        assert!(description.html.unwrap().starts_with("<p>"));
+    }
+
+    #[tokio::test]
+    async fn replays_referents_and_annotations() {
+        let genius = genius_with(Arc::new(ReplayTransport::new(replay_dir())));
+
+        let options = ReferentsOptions {
+            text_format: TextFormat::Dom,
+            ..Default::default()
+        };
+        let referents = genius.referents(ReferentsTarget::Song(100337), options).await.unwrap();
+        assert_eq!(referents.len(), 2);
+        let referent = &referents[0];
+        assert_eq!(referent.range.as_ref().unwrap().content, referent.fragment);
+        assert_eq!(referent.annotatable.r#type, "Song");
+        let annotation = &referent.annotations[0];
+        assert_eq!(annotation.votes_total, 12);
+        assert_eq!(annotation.authors.len(), 2);
+        assert!(annotation.verified_by.is_none());
+        assert!(annotation.current_user_metadata.is_none());
+        assert!(annotation.body.plain.is_none() && annotation.body.html.is_none());
+        assert_eq!(annotation.body.dom.as_ref().unwrap()["tag"], "root");
+        assert!(referents[1].is_description);
+        assert!(referents[1].annotations.is_empty());
+
+        let annotation = genius.annotations(2871517, TextFormat::Html).await.unwrap();
+        assert_eq!(annotation.votes_total, 12);
+        assert_eq!(annotation.body.html.as_deref(), Some("<p>The narrator’s love is <em>possessive</em>.</p>"));
+        assert!(annotation.body.dom.is_none());
+        let metadata = annotation.current_user_metadata.unwrap();
+        assert_eq!(metadata.interactions.unwrap().vote, Some(Vote::Up));
+        assert!(metadata.excluded_permissions.contains(&String::from("edit")));
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_replay_without_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = RecordingTransport::new(ReplayTransport::new(replay_dir()), dir.path());
+        let recorded = GeniusBuilder::new()
+            .token("secret-token")
+            .transport(Arc::new(recording))
+            .build()
+            .unwrap()
+            .songs(100337)
+            .await
+            .unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        assert!(!std::fs::read_to_string(&files[0]).unwrap().contains("secret-token"));
+
+        let replayed = genius_with(Arc::new(ReplayTransport::new(dir.path()))).songs(100337).await.unwrap();
+        assert_eq!(replayed.id, recorded.id);
+        assert_eq!(replayed.url, recorded.url);
+    }
+
+    #[tokio::test]
+    async fn web_page_lookup_without_url_is_not_sent() {
+        // Nothing listens on the discard port, so a request that is sent fails with a transport error.
+        let genius = GeniusBuilder::new().token("token").base_url("http://127.0.0.1:9").build().unwrap();
+        let result = genius.web_pages_lookup(WebPageLookup::default()).await;
+        assert!(matches!(result, Err(GeniusError::InvalidRequest { .. })), "{:?}", result.err());
+    }
+
+    #[tokio::test]
+    async fn unsuccessful_statuses_map_to_errors() {
+        let fixtures = Fixtures::new();
+        fixtures
+            .respond(
+                "/songs/1",
+                &[],
+                401,
+                &[],
+                r#"{"error":"invalid_token","error_description":"The access token is invalid"}"#,
+            )
+            .respond("/songs/2", &[], 404, &[], r#"{"meta":{"status":404,"message":"Not found"}}"#)
+            .respond("/songs/3", &[], 429, &[("Retry-After", "7")], "")
+            .respond("/songs/4", &[], 503, &[], "<html>Service Unavailable</html>");
+        let genius = fixtures.genius();
+
+        match genius.songs(1).await {
+            Err(GeniusError::Unauthorized { message }) => assert_eq!(message, "The access token is invalid"),
+            other => panic!("{:?}", other),
+        }
+        match genius.songs(2).await {
+            Err(GeniusError::NotFound { message }) => assert_eq!(message, "Not found"),
+            other => panic!("{:?}", other),
+        }
+        match genius.songs(3).await {
+            Err(GeniusError::RateLimited { retry_after }) => assert_eq!(retry_after, Some(Duration::from_secs(7))),
+            other => panic!("{:?}", other),
+        }
+        match genius.songs(4).await {
+            Err(GeniusError::Server { status, message }) => {
+                assert_eq!(status, 503);
+                assert_eq!(message, "Service Unavailable");
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn failures_reported_in_meta_map_to_errors() {
+        let fixtures = Fixtures::new();
+        fixtures
+            .respond("/songs/1", &[], 200, &[], r#"{"meta":{"status":404,"message":"Not found"}}"#)
+            .respond("/songs/2", &[], 200, &[], r#"{"meta":{"status":403,"message":"Forbidden"},"response":{}}"#);
+        let genius = fixtures.genius();
+
+        assert!(matches!(genius.songs(1).await, Err(GeniusError::NotFound { .. })));
+        match genius.songs(2).await {
+            Err(GeniusError::Api { status, message }) => {
+                assert_eq!(status, 403);
+                assert_eq!(message, "Forbidden");
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_payloads_keep_the_beginning_of_the_body() {
+        let body = format!(r#"{{"meta":{{"status":200}},"response":{{"song":"{}"#, "x".repeat(300));
+        let fixtures = Fixtures::new();
+        fixtures.respond("/songs/1", &[], 200, &[], &body);
+
+        match fixtures.genius().songs(1).await {
+            Err(GeniusError::Decode { body: snippet, .. }) => {
+                assert_eq!(snippet.chars().count(), 200);
+                assert!(body.starts_with(&snippet));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn responses_without_the_item_are_empty() {
+        let fixtures = Fixtures::new();
+        fixtures
+            .ok("/songs/1", &[], serde_json::json!({}))
+            .respond("/songs/2", &[], 200, &[], r#"{"meta":{"status":200}}"#);
+        let genius = fixtures.genius();
+
+        assert!(matches!(genius.songs(1).await, Err(GeniusError::EmptyResponse)));
+        assert!(matches!(genius.songs(2).await, Err(GeniusError::EmptyResponse)));
+    }
+}
@@ -1,3 +1,13 @@
+pub mod auth;
+pub mod builder;
+pub mod error;
 pub mod model;
 pub mod genius;
+pub mod pagination;
+pub mod progress;
+pub mod resolver;
+pub mod retry;
+mod album_lookup;
 mod constants;
+#[cfg(test)]
+mod test_support;
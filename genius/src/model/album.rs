@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+use super::artist::PrimaryArtist;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Album {
+    pub api_path: String,
+    pub cover_art_url: Option<String>,
+    pub full_title: String,
+    pub id: u32,
+    pub name: String,
+    pub release_date_for_display: Option<String>,
+    pub url: String,
+    pub artist: Option<PrimaryArtist>,
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use super::{text::TextBody, user::User};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Annotation {
+    pub api_path: String,
+    #[serde(default)]
+    pub authors: Vec<AnnotationAuthor>,
+    pub body: TextBody,
+    pub comment_count: Option<u32>,
+    #[serde(default)]
+    pub community: bool,
+    #[serde(default)]
+    pub cosigned_by: Vec<User>,
+    pub current_user_metadata: Option<CurrentUserMetadata>,
+    #[serde(default)]
+    pub has_voters: bool,
+    pub id: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    pub share_url: Option<String>,
+    pub source: Option<String>,
+    pub state: String,
+    pub url: String,
+    #[serde(default)]
+    pub verified: bool,
+    pub verified_by: Option<User>,
+    #[serde(default)]
+    pub votes_total: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnnotationAuthor {
+    /// Share of the annotation written by this author, between 0 and 1.
+    pub attribution: f64,
+    pub pinned_role: Option<String>,
+    pub user: User,
+}
+
+/// What the authenticated user can do with the annotation and how they voted on it.
+/// Only present for user-scoped tokens.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CurrentUserMetadata {
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub excluded_permissions: Vec<String>,
+    pub interactions: Option<Interactions>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Interactions {
+    #[serde(default)]
+    pub cosign: bool,
+    #[serde(default)]
+    pub pyong: bool,
+    pub vote: Option<Vote>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Vote {
+    Up,
+    Down,
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// A section of lyrics introduced by a header such as `[Verse 1: Kendrick Lamar]`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LyricSection {
+    pub kind: SectionKind,
+    /// The header without its brackets and performers, e.g. `Verse 1`. Empty for lines preceding the first header.
+    pub label: String,
+    /// Performers listed after the colon of the header, if any. Splitting the list is a best effort,
+    /// see `credit` for the header as written.
+    pub performers: Vec<String>,
+    /// The text after the colon of the header, e.g. `Tyler, The Creator & Frank Ocean`. Empty if there is none.
+    #[serde(default)]
+    pub credit: String,
+    pub lines: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionKind {
+    Intro,
+    Verse,
+    PreChorus,
+    Chorus,
+    PostChorus,
+    Hook,
+    Refrain,
+    Bridge,
+    Interlude,
+    Breakdown,
+    Instrumental,
+    Skit,
+    Outro,
+    /// Headers that are not recognized, and lines preceding the first header.
+    Other,
+}
+
+/// A fragment of the lyrics annotated on Genius.
+///
+/// Offsets are character (not byte) offsets into the scraped lyrics, `end` being exclusive.
+/// `referent_id` identifies the [`Referent`](super::referent::Referent) holding the annotations.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AnnotatedSpan {
+    pub start: usize,
+    pub end: usize,
+    pub fragment: String,
+    pub referent_id: u32,
+}
+
+/// A line of time-tagged lyrics, e.g. from an LRC file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SyncedLine {
+    /// Milliseconds from the start of the song.
+    pub time_ms: u64,
+    pub text: String,
+}
+
+/// Why a song has (or lacks) lyrics in the output.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LyricsStatus {
+    /// Lyrics were scraped.
+    Available,
+    /// Genius marks the song as an instrumental.
+    Instrumental,
+    /// Genius says the lyrics have yet to be released.
+    Unreleased,
+    /// The page has neither lyrics nor a placeholder explaining why, e.g. because its layout changed.
+    NoContainer,
+    /// The page responded with an error status.
+    HttpError,
+    /// The page could not be fetched, e.g. because of a network error.
+    Failed,
+    /// The site's `robots.txt` disallows scraping the page.
+    Disallowed,
+    /// No lyrics source has the song.
+    NotFound,
+    /// The lyrics have not been scraped, e.g. in files written by older versions.
+    #[default]
+    Unknown,
+}
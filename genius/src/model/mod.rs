@@ -1,4 +1,12 @@
+pub mod album;
+pub mod annotation;
 pub mod artist;
 pub mod hit;
+pub mod lyrics;
+pub mod page;
+pub mod referent;
 pub mod responses;
-pub mod song;
\ No newline at end of file
+pub mod song;
+pub mod text;
+pub mod user;
+pub mod web_page;
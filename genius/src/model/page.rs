@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+/// Song metadata embedded in a Genius song page, some of which the API does not expose.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SongPageMetadata {
+    pub song_id: Option<u32>,
+    pub title: Option<String>,
+    pub album: Option<PageAlbum>,
+    /// Tags such as genres, primary tag first.
+    pub tags: Vec<PageTag>,
+    pub release_date: Option<String>,
+    pub pageviews: Option<u64>,
+    pub writers: Vec<String>,
+    pub producers: Vec<String>,
+    /// Additional credits such as "Mixing Engineer" or "Label".
+    pub credits: Vec<PageCredit>,
+    /// Community translations linked from the page.
+    #[serde(default)]
+    pub translations: Vec<TranslationLink>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageAlbum {
+    pub id: u32,
+    pub name: String,
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageTag {
+    pub id: u32,
+    pub name: String,
+    pub primary: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PageCredit {
+    pub label: String,
+    pub artists: Vec<String>,
+}
+
+/// A community translation of a song, published as a separate song such as "Genius English Translations".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TranslationLink {
+    /// A language code such as `en`, or the language name shown on pages without one.
+    pub language: String,
+    pub song_id: Option<u32>,
+    pub title: Option<String>,
+    pub url: String,
+}
+
+/// The tracklist of a Genius album page, in album order.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlbumTracklist {
+    pub album_id: Option<u32>,
+    pub name: String,
+    pub artist: Option<String>,
+    pub url: String,
+    pub tracks: Vec<AlbumTrack>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlbumTrack {
+    /// `None` for tracks listed without a number, such as bonus tracks on some pages.
+    pub number: Option<u32>,
+    pub title: String,
+    /// `None` for tracks without a song page.
+    pub url: Option<String>,
+}
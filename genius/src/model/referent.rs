@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::annotation::Annotation;
+
+/// A fragment of a song or web page that has been annotated.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Referent {
+    pub annotatable: Annotatable,
+    pub annotator_id: Option<u32>,
+    pub annotator_login: Option<String>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    pub api_path: String,
+    pub classification: String,
+    pub fragment: String,
+    pub id: u32,
+    #[serde(default)]
+    pub is_description: bool,
+    pub path: String,
+    pub range: Option<ReferentRange>,
+    pub song_id: Option<u32>,
+    pub url: String,
+    #[serde(default)]
+    pub verified_annotator_ids: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Annotatable {
+    pub api_path: String,
+    pub context: Option<String>,
+    pub id: u32,
+    pub image_url: Option<String>,
+    pub link_title: Option<String>,
+    pub title: String,
+    pub r#type: String,
+    pub url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReferentRange {
+    pub content: String,
+}
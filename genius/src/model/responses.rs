@@ -1,10 +1,44 @@
 use serde::{Deserialize, Serialize};
 
-use super::{artist::Artist, hit::Hit, song::ArtistSong};
+use super::{
+    annotation::Annotation,
+    artist::Artist,
+    hit::Hit,
+    referent::Referent,
+    song::{ArtistSong, SongDetail},
+    user::User,
+    web_page::WebPage,
+};
 
 #[derive(Deserialize, Debug)]
 pub struct WrappedResponse<T> {
-    pub response: T,
+    pub meta: Option<Meta>,
+    /// Missing when `meta` reports a failure.
+    pub response: Option<T>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Meta {
+    pub status: u16,
+    pub message: Option<String>,
+}
+
+/// Body of an unsuccessful response. Genius either wraps the failure in `meta`
+/// or, for authentication failures, returns an OAuth-style `error` object.
+#[derive(Deserialize, Debug)]
+pub struct ErrorEnvelope {
+    pub meta: Option<Meta>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+impl ErrorEnvelope {
+    pub fn message(self) -> Option<String> {
+        self.meta
+            .and_then(|meta| meta.message)
+            .or(self.error_description)
+            .or(self.error)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -19,6 +53,7 @@ pub struct ArtistResponse {
 #[derive(Deserialize, Debug)]
 pub struct ArtistSongsResponse {
     pub songs: Option<Vec<ArtistSong>>,
+    pub next_page: Option<u32>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -29,4 +64,30 @@ pub struct LyricsResponse {
 #[derive(Deserialize, Debug, Serialize, Clone)]
 pub struct SongResponse {
     pub song: Option<ArtistSong>,
-}
\ No newline at end of file
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SongDetailResponse {
+    pub song: Option<SongDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReferentsResponse {
+    pub referents: Option<Vec<Referent>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnnotationResponse {
+    pub annotation: Option<Annotation>,
+    pub referent: Option<Referent>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct WebPageResponse {
+    pub web_page: Option<WebPage>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AccountResponse {
+    pub user: Option<User>,
+}
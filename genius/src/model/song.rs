@@ -1,6 +1,14 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::artist::PrimaryArtist;
+use super::{
+    album::Album,
+    artist::PrimaryArtist,
+    lyrics::{AnnotatedSpan, LyricSection, LyricsStatus, SyncedLine},
+    page::SongPageMetadata,
+    text::TextBody,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ArtistSong {
@@ -31,6 +39,12 @@ impl ArtistSong {
         ArtistSongWithLyrics {
             song: self.clone(),
             lyrics,
+            lyrics_status: LyricsStatus::default(),
+            sections: None,
+            annotations: None,
+            metadata: None,
+            synced: None,
+            translations: None,
         }
     }
 }
@@ -39,4 +53,90 @@ impl ArtistSong {
 pub struct ArtistSongWithLyrics {
     pub song: ArtistSong,
     pub lyrics: String,
+    #[serde(default)]
+    pub lyrics_status: LyricsStatus,
+    /// The lyrics split by section headers, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sections: Option<Vec<LyricSection>>,
+    /// The annotated fragments of the lyrics, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Vec<AnnotatedSpan>>,
+    /// Metadata embedded in the song page, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SongPageMetadata>,
+    /// Time-tagged lyrics, ordered by time, when imported from an LRC file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synced: Option<Vec<SyncedLine>>,
+    /// Lyrics of the community translations of the song by language, when requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translations: Option<BTreeMap<String, String>>,
+}
+
+/// A song as returned by `/songs/:id`, including album, credits, media and relationships.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SongDetail {
+    #[serde(flatten)]
+    pub song: ArtistSong,
+    pub album: Option<Album>,
+    pub apple_music_id: Option<String>,
+    #[serde(default)]
+    pub custom_performances: Vec<CustomPerformance>,
+    pub description: Option<TextBody>,
+    #[serde(default)]
+    pub featured_artists: Vec<PrimaryArtist>,
+    #[serde(default)]
+    pub media: Vec<SongMedia>,
+    #[serde(default)]
+    pub producer_artists: Vec<PrimaryArtist>,
+    pub recording_location: Option<String>,
+    pub release_date: Option<String>,
+    pub release_date_components: Option<ReleaseDateComponents>,
+    #[serde(default)]
+    pub song_relationships: Vec<SongRelationship>,
+    pub stats: Option<SongStats>,
+    #[serde(default)]
+    pub writer_artists: Vec<PrimaryArtist>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReleaseDateComponents {
+    pub year: Option<u16>,
+    pub month: Option<u8>,
+    pub day: Option<u8>,
+}
+
+/// Additional credits such as "Mixing Engineer" or "Label".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomPerformance {
+    pub label: String,
+    pub artists: Vec<PrimaryArtist>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SongMedia {
+    pub provider: String,
+    pub r#type: String,
+    pub url: String,
+    pub start: Option<u32>,
+}
+
+/// Related songs grouped by relationship, e.g. `samples`, `sampled_in`, `cover_of` or `interpolates`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SongRelationship {
+    pub relationship_type: String,
+    pub r#type: String,
+    pub songs: Vec<ArtistSong>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SongStats {
+    pub accepted_annotations: Option<u32>,
+    pub contributors: Option<u32>,
+    #[serde(default)]
+    pub hot: bool,
+    pub iq_earners: Option<u32>,
+    pub pageviews: Option<u64>,
+    pub transcribers: Option<u32>,
+    pub unreviewed_annotations: Option<u32>,
+    pub verified_annotations: Option<u32>,
 }
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A rich text field. Genius only fills the representation requested with `text_format`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TextBody {
+    pub plain: Option<String>,
+    pub html: Option<String>,
+    pub dom: Option<Value>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct User {
+    pub api_path: String,
+    pub email: Option<String>,
+    pub header_image_url: Option<String>,
+    pub human_readable_role_for_display: Option<String>,
+    pub id: u32,
+    pub iq: Option<i64>,
+    pub login: String,
+    pub name: String,
+    pub role_for_display: Option<String>,
+    pub url: String,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebPage {
+    pub annotation_count: Option<u32>,
+    pub api_path: String,
+    pub domain: Option<String>,
+    pub id: u32,
+    pub normalized_url: Option<String>,
+    pub share_url: Option<String>,
+    pub title: Option<String>,
+    pub url: String,
+}
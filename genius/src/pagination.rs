@@ -0,0 +1,348 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    constants::{PAGE_INDEX_PARAM, PER_PAGE_PARAM, PER_PAGE_PARAM_LIMIT},
+    error::GeniusError,
+    genius::{Genius, Result},
+    progress::ProgressObserver,
+};
+
+/// Limits applied to a paginated request.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PageOptions {
+    /// Stops after this many items have been yielded.
+    pub max_items: Option<usize>,
+    /// Stops after this many pages have been fetched.
+    pub max_pages: Option<u32>,
+    /// Fetches up to this many pages at once. `None` fetches them one after another.
+    pub concurrency: Option<usize>,
+}
+
+/// A page of a paginated response.
+pub(crate) trait Page: DeserializeOwned {
+    type Item;
+    fn into_items(self) -> Vec<Self::Item>;
+    /// Index of the page following `current`, or `None` if `current` is the last one.
+    fn next_page(&self, current: u32) -> Option<u32>;
+}
+
+/// Lazily walks the pages of a Genius endpoint, starting at page 1.
+///
+/// Pages are requested in windows of `concurrency` pages, one at a time by default. A window is
+/// only requested when the stream is polled past the items of the previous one, so dropping the
+/// stream stops pagination. Pages are always yielded in API order.
+pub(crate) struct Paginator<'a> {
+    genius: &'a Genius,
+    path: String,
+    query: Vec<(&'static str, String)>,
+    options: PageOptions,
+}
+
+impl<'a> Paginator<'a> {
+    pub(crate) fn new(
+        genius: &'a Genius,
+        path: String,
+        query: Vec<(&'static str, String)>,
+        options: PageOptions,
+    ) -> Self {
+        Self {
+            genius,
+            path,
+            query,
+            options,
+        }
+    }
+
+    /// Streams the pages, ending at the first empty page or when Genius reports no next page.
+    ///
+    /// Pages of a window that follow the last one are discarded. If a request of a window fails,
+    /// the pages preceding it are yielded before the error.
+    pub(crate) fn pages<T>(self) -> impl Stream<Item = Result<Vec<T::Item>>> + 'a
+    where
+        T: Page + 'a,
+    {
+        let progress = PaginationProgress {
+            observer: Arc::clone(&self.genius.observer),
+            endpoint: self.path.clone(),
+            pages: 0,
+            total: 0,
+        };
+        let state = PageWindow {
+            paginator: self,
+            next_page: Some(1),
+            fetched: VecDeque::new(),
+            error: None,
+            progress,
+        };
+        stream::try_unfold(state, |mut state| async move {
+            if state.fetched.is_empty() {
+                state.fetch::<T>().await;
+            }
+            match state.fetched.pop_front() {
+                Some(items) => Ok(Some((items, state))),
+                None => state.error.take().map_or(Ok(None), Err),
+            }
+        })
+    }
+
+    /// Streams the items of every page, honoring `max_items`.
+    pub(crate) fn items<T>(self) -> impl Stream<Item = Result<T::Item>> + 'a
+    where
+        T: Page + 'a,
+        T::Item: 'a,
+    {
+        let max_items = self.options.max_items.unwrap_or(usize::MAX);
+        self.pages::<T>()
+            .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+            .try_flatten()
+            .take(max_items)
+    }
+
+    async fn fetch_page<T: Page>(&self, page: u32) -> Result<T> {
+        let mut query = self.query.clone();
+        query.push((PAGE_INDEX_PARAM, page.to_string()));
+        query.push((PER_PAGE_PARAM, String::from(PER_PAGE_PARAM_LIMIT)));
+        self.genius.get::<T>(&self.path, &query).await
+    }
+}
+
+/// Pagination state: pages fetched by the last window and not yielded yet.
+struct PageWindow<'a, I> {
+    paginator: Paginator<'a>,
+    next_page: Option<u32>,
+    fetched: VecDeque<Vec<I>>,
+    error: Option<GeniusError>,
+    progress: PaginationProgress,
+}
+
+impl<I> PageWindow<'_, I> {
+    /// Requests the next window of pages, assuming they are numbered consecutively.
+    async fn fetch<T: Page<Item = I>>(&mut self) {
+        let options = self.paginator.options;
+        let mut size = options.concurrency.unwrap_or(1).max(1) as u32;
+        if let Some(max_pages) = options.max_pages {
+            size = size.min(max_pages.saturating_sub(self.progress.pages));
+        }
+        let first = match self.next_page.take() {
+            Some(page) if size > 0 => page,
+            _ => return,
+        };
+
+        let pages = first..first + size;
+        let responses = future::join_all(pages.clone().map(|page| self.paginator.fetch_page::<T>(page))).await;
+        for (page, response) in pages.zip(responses) {
+            let response = match response {
+                Ok(response) => response,
+                Err(err) => {
+                    self.error = Some(err);
+                    return;
+                }
+            };
+            let next_page = response.next_page(page);
+            let items = response.into_items();
+            if items.is_empty() {
+                return;
+            }
+            self.progress.page_fetched(page, items.len());
+            self.fetched.push_back(items);
+
+            if next_page != Some(page + 1) {
+                self.next_page = next_page;
+                return;
+            }
+        }
+        self.next_page = Some(first + size);
+    }
+}
+
+/// Reports fetched pages to the client's observer, and the end of pagination once dropped,
+/// which also covers consumers that drop the stream before the last page.
+struct PaginationProgress {
+    observer: Arc<dyn ProgressObserver>,
+    endpoint: String,
+    pages: u32,
+    total: usize,
+}
+
+impl PaginationProgress {
+    fn page_fetched(&mut self, page: u32, items: usize) {
+        self.pages += 1;
+        self.total += items;
+        self.observer.on_page(&self.endpoint, page, items, self.total);
+    }
+}
+
+impl Drop for PaginationProgress {
+    fn drop(&mut self) {
+        self.observer.on_finished(&self.endpoint, self.pages, self.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{StreamExt, TryStreamExt};
+    use serde_json::{json, Value};
+
+    use crate::{error::GeniusError, genius::ArtistSongsOptions, test_support::Fixtures};
+
+    const SONG_FIXTURE: &str = include_str!("../tests/fixtures/replay/get-882302f79da176be.json");
+
+    /// The recorded song, renumbered.
+    fn song(id: u32) -> Value {
+        let fixture: Value = serde_json::from_str(SONG_FIXTURE).unwrap();
+        let body: Value = serde_json::from_str(fixture["body"].as_str().unwrap()).unwrap();
+        let mut song = body["response"]["song"].clone();
+        song["id"] = json!(id);
+        song
+    }
+
+    fn page_query(page: u32) -> [(&'static str, String); 2] {
+        [("page", page.to_string()), ("per_page", String::from("50"))]
+    }
+
+    /// Answers page `page` of the songs of artist 1 with `ids`.
+    fn page(fixtures: &Fixtures, page: u32, ids: &[u32], next_page: Option<u32>) {
+        let songs: Vec<_> = ids.iter().map(|id| song(*id)).collect();
+        fixtures.ok("/artists/1/songs", &page_query(page), json!({ "songs": songs, "next_page": next_page }));
+    }
+
+    fn requested_pages(fixtures: &Fixtures) -> Vec<u32> {
+        fixtures
+            .requests()
+            .iter()
+            .filter_map(|request| request.query.iter().find(|(name, _)| name == "page"))
+            .map(|(_, page)| page.parse().unwrap())
+            .collect()
+    }
+
+    async fn song_ids(fixtures: &Fixtures, options: ArtistSongsOptions) -> Vec<u32> {
+        let genius = fixtures.genius();
+        let songs: Vec<_> = genius.artists_songs_stream(1, options).try_collect().await.unwrap();
+        songs.iter().map(|song| song.id).collect()
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_items_without_requesting_another_page() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1, 2, 3], Some(2));
+        page(&fixtures, 2, &[4, 5, 6], None);
+        let options = ArtistSongsOptions {
+            max_items: Some(3),
+            ..Default::default()
+        };
+
+        assert_eq!(song_ids(&fixtures, options).await, [1, 2, 3]);
+        assert_eq!(requested_pages(&fixtures), [1]);
+    }
+
+    #[tokio::test]
+    async fn follows_next_page() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1, 2], Some(3));
+        page(&fixtures, 3, &[5, 6], None);
+
+        assert_eq!(song_ids(&fixtures, ArtistSongsOptions::default()).await, [1, 2, 5, 6]);
+        assert_eq!(requested_pages(&fixtures), [1, 3]);
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_pages() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1], Some(2));
+        page(&fixtures, 2, &[2], Some(3));
+        page(&fixtures, 3, &[3], None);
+        let options = ArtistSongsOptions {
+            max_pages: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(song_ids(&fixtures, options).await, [1, 2]);
+        assert_eq!(requested_pages(&fixtures), [1, 2]);
+    }
+
+    fn concurrently(concurrency: usize) -> ArtistSongsOptions {
+        ArtistSongsOptions {
+            concurrency: Some(concurrency),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_api_order_when_pages_complete_out_of_order() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1, 2], Some(2));
+        page(&fixtures, 2, &[3, 4], Some(3));
+        page(&fixtures, 3, &[5, 6], None);
+        fixtures
+            .delay("/artists/1/songs", &page_query(1), Duration::from_millis(60))
+            .delay("/artists/1/songs", &page_query(2), Duration::from_millis(30));
+
+        assert_eq!(song_ids(&fixtures, concurrently(3)).await, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test]
+    async fn window_ends_at_the_first_empty_page() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1], Some(2));
+        page(&fixtures, 2, &[], Some(3));
+        page(&fixtures, 3, &[3], None);
+
+        assert_eq!(song_ids(&fixtures, concurrently(3)).await, [1]);
+        assert_eq!(requested_pages(&fixtures), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn window_ends_at_the_first_page_without_next_page() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1], None);
+        page(&fixtures, 2, &[2], Some(3));
+        page(&fixtures, 3, &[3], Some(4));
+
+        assert_eq!(song_ids(&fixtures, concurrently(3)).await, [1]);
+        assert_eq!(requested_pages(&fixtures), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn max_pages_shrinks_the_last_window() {
+        let fixtures = Fixtures::new();
+        for number in 1..=4 {
+            page(&fixtures, number, &[number], Some(number + 1));
+        }
+        let options = ArtistSongsOptions {
+            max_pages: Some(3),
+            ..concurrently(2)
+        };
+
+        assert_eq!(song_ids(&fixtures, options).await, [1, 2, 3]);
+        assert_eq!(requested_pages(&fixtures), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn failures_after_the_last_page_are_ignored() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1], Some(2));
+        page(&fixtures, 2, &[2], None);
+        fixtures.respond("/artists/1/songs", &page_query(3), 500, &[], "");
+
+        assert_eq!(song_ids(&fixtures, concurrently(3)).await, [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn failures_within_the_pages_follow_the_preceding_pages() {
+        let fixtures = Fixtures::new();
+        page(&fixtures, 1, &[1], Some(2));
+        fixtures.respond("/artists/1/songs", &page_query(2), 500, &[], "");
+        page(&fixtures, 3, &[3], None);
+
+        let genius = fixtures.genius();
+        let pages: Vec<_> = genius.artists_songs_pages(1, concurrently(3)).collect().await;
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].as_ref().unwrap()[0].id, 1);
+        assert!(matches!(pages[1], Err(GeniusError::Server { status: 500, .. })));
+    }
+}
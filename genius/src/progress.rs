@@ -0,0 +1,149 @@
+use std::{io::Write, sync::Mutex, time::Duration};
+
+use log::error;
+use serde_json::{json, Value};
+
+use crate::error::GeniusError;
+
+/// Receives progress events from the [`Genius`](crate::genius::Genius) client.
+///
+/// `endpoint` is the path of the request, e.g. `/artists/1421/songs`. Every method has an
+/// empty default implementation, so observers only implement the events they care about.
+pub trait ProgressObserver: Send + Sync {
+    /// A page of a paginated endpoint has been fetched. `total` counts the items of all pages so far.
+    fn on_page(&self, _endpoint: &str, _page: u32, _items: usize, _total: usize) {}
+
+    /// A request failed and will be sent again after `delay`. `attempt` is the upcoming attempt.
+    fn on_retry(&self, _endpoint: &str, _attempt: u32, _delay: Duration, _error: &GeniusError) {}
+
+    /// A request failed for good.
+    fn on_error(&self, _endpoint: &str, _error: &GeniusError) {}
+
+    /// Pagination of an endpoint ended, either because all pages were fetched or because
+    /// the consumer stopped early.
+    fn on_finished(&self, _endpoint: &str, _pages: u32, _total: usize) {}
+}
+
+/// Ignores every event. Used when no observer is configured.
+pub struct NoopObserver;
+
+impl ProgressObserver for NoopObserver {}
+
+/// Writes every event as a JSON object on its own line, for machine consumers.
+pub struct JsonLinesObserver<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// The writer, e.g. to read back the events written to a buffer.
+    pub fn into_inner(self) -> W {
+        match self.writer.into_inner() {
+            Ok(writer) => writer,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    fn emit(&self, event: Value) {
+        let mut writer = match self.writer.lock() {
+            Ok(writer) => writer,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(err) = writeln!(writer, "{}", event).and_then(|_| writer.flush()) {
+            error!("Could not write progress event: {}", err);
+        }
+    }
+}
+
+impl JsonLinesObserver<std::io::Stdout> {
+    pub fn stdout() -> Self {
+        Self::new(std::io::stdout())
+    }
+}
+
+impl<W: Write + Send> ProgressObserver for JsonLinesObserver<W> {
+    fn on_page(&self, endpoint: &str, page: u32, items: usize, total: usize) {
+        self.emit(json!({
+            "event": "page",
+            "endpoint": endpoint,
+            "page": page,
+            "items": items,
+            "total": total,
+        }));
+    }
+
+    fn on_retry(&self, endpoint: &str, attempt: u32, delay: Duration, error: &GeniusError) {
+        self.emit(json!({
+            "event": "retry",
+            "endpoint": endpoint,
+            "attempt": attempt,
+            "delay_ms": delay.as_millis() as u64,
+            "status": error.status(),
+            "error": error.to_string(),
+        }));
+    }
+
+    fn on_error(&self, endpoint: &str, error: &GeniusError) {
+        self.emit(json!({
+            "event": "error",
+            "endpoint": endpoint,
+            "status": error.status(),
+            "error": error.to_string(),
+        }));
+    }
+
+    fn on_finished(&self, endpoint: &str, pages: u32, total: usize) {
+        self.emit(json!({
+            "event": "finished",
+            "endpoint": endpoint,
+            "pages": pages,
+            "total": total,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_one_json_object_per_event() {
+        let observer = JsonLinesObserver::new(Vec::new());
+        observer.on_page("/artists/1/songs", 1, 50, 50);
+        let rate_limited = GeniusError::RateLimited {
+            retry_after: Some(Duration::from_secs(2)),
+        };
+        observer.on_retry("/artists/1/songs", 2, Duration::from_millis(2000), &rate_limited);
+        observer.on_error("/songs/1", &GeniusError::EmptyResponse);
+        observer.on_finished("/artists/1/songs", 1, 50);
+
+        let output = String::from_utf8(observer.into_inner()).unwrap();
+        let events: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(
+            events,
+            [
+                json!({ "event": "page", "endpoint": "/artists/1/songs", "page": 1, "items": 50, "total": 50 }),
+                json!({
+                    "event": "retry",
+                    "endpoint": "/artists/1/songs",
+                    "attempt": 2,
+                    "delay_ms": 2000,
+                    "status": 429,
+                    "error": "Rate limited, retry after 2s",
+                }),
+                json!({
+                    "event": "error",
+                    "endpoint": "/songs/1",
+                    "status": null,
+                    "error": "No item has been returned",
+                }),
+                json!({ "event": "finished", "endpoint": "/artists/1/songs", "pages": 1, "total": 50 }),
+            ]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+
+use futures::future;
+use log::warn;
+
+use crate::{
+    error::GeniusError,
+    genius::{Genius, Result},
+    model::hit::Hit,
+};
+
+const NAME_WEIGHT: f64 = 0.8;
+const FREQUENCY_WEIGHT: f64 = 0.2;
+
+/// How an artist name matched the query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    /// Same name, ignoring case.
+    Exact,
+    /// Same name once case, punctuation, spacing and a leading "The" are ignored.
+    Normalized,
+    /// One of the artist's alternate names matches.
+    AlternateName,
+    /// The query is made of leading words of the name, e.g. "kendrick" for "Kendrick Lamar".
+    Partial,
+    /// The name is only similar to the query.
+    Fuzzy,
+}
+
+/// An artist found in the search hits, with its score. Higher scores are better.
+#[derive(Debug, Clone)]
+pub struct ArtistCandidate {
+    pub id: u32,
+    pub name: String,
+    /// Empty unless the artist has been looked up, see [`ResolveOptions::lookups`].
+    pub alternate_names: Vec<String>,
+    /// Number of search hits whose primary artist is this artist.
+    pub hits: usize,
+    pub match_kind: MatchKind,
+    /// Between 0 and 1.
+    pub score: f64,
+}
+
+pub struct ResolveOptions {
+    /// Number of pages of search hits from which candidates are gathered.
+    pub search_pages: u32,
+    /// Number of best candidates looked up to score their alternate names.
+    pub lookups: usize,
+}
+
+impl Default for ResolveOptions {
+    fn default() -> Self {
+        Self {
+            search_pages: 3,
+            lookups: 5,
+        }
+    }
+}
+
+impl Genius {
+    /// Finds the artists matching `name`, best match first.
+    ///
+    /// Candidates are the primary artists of the hits of several search pages. They are scored on
+    /// how well their name (or one of their alternate names) matches `name` and on how often they
+    /// appear in the hits. Fails with [`GeniusError::NotFound`] when the search has no hit.
+    pub async fn resolve_artist(&self, name: &str, options: ResolveOptions) -> Result<Vec<ArtistCandidate>> {
+        let mut hits = vec![];
+        for page in 1..=options.search_pages {
+            let page_hits = self.search_page(name, page).await?;
+            if page_hits.is_empty() {
+                break;
+            }
+            hits.extend(page_hits);
+        }
+
+        if hits.is_empty() {
+            return Err(GeniusError::NotFound {
+                message: format!("No search hit for `{}`", name),
+            });
+        }
+
+        let mut candidates = candidates_from_hits(name, &hits);
+        sort_candidates(&mut candidates);
+
+        let lookups = options.lookups.min(candidates.len());
+        let artists = future::join_all(candidates[..lookups].iter().map(|candidate| self.artists(candidate.id))).await;
+        for (candidate, artist) in candidates.iter_mut().zip(artists) {
+            match artist {
+                Ok(artist) => {
+                    candidate.alternate_names = artist.alternate_names;
+                    score(name, candidate, hits.len());
+                }
+                Err(err) => warn!("Could not look up artist `{}`: {}", candidate.name, err),
+            }
+        }
+        sort_candidates(&mut candidates);
+
+        Ok(candidates)
+    }
+}
+
+fn candidates_from_hits(name: &str, hits: &[Hit]) -> Vec<ArtistCandidate> {
+    let mut candidates: HashMap<u32, ArtistCandidate> = HashMap::new();
+    for hit in hits {
+        let artist = &hit.result.primary_artist;
+        candidates
+            .entry(artist.id)
+            .or_insert_with(|| ArtistCandidate {
+                id: artist.id,
+                name: artist.name.clone(),
+                alternate_names: vec![],
+                hits: 0,
+                match_kind: MatchKind::Fuzzy,
+                score: 0.0,
+            })
+            .hits += 1;
+    }
+
+    let mut candidates: Vec<_> = candidates.into_values().collect();
+    for candidate in candidates.iter_mut() {
+        score(name, candidate, hits.len());
+    }
+    candidates
+}
+
+fn sort_candidates(candidates: &mut [ArtistCandidate]) {
+    candidates.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.hits.cmp(&a.hits))
+            .then(a.name.cmp(&b.name))
+    });
+}
+
+fn score(query: &str, candidate: &mut ArtistCandidate, total_hits: usize) {
+    let (match_kind, name_score) = std::iter::once(&candidate.name)
+        .map(|name| match_name(query, name))
+        .chain(candidate.alternate_names.iter().map(|name| {
+            let (match_kind, score) = match_name(query, name);
+            match match_kind {
+                MatchKind::Exact | MatchKind::Normalized => (MatchKind::AlternateName, 0.85),
+                _ => (match_kind, score * 0.9),
+            }
+        }))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap_or((MatchKind::Fuzzy, 0.0));
+    let frequency = candidate.hits as f64 / total_hits.max(1) as f64;
+
+    candidate.match_kind = match_kind;
+    candidate.score = NAME_WEIGHT * name_score + FREQUENCY_WEIGHT * frequency;
+}
+
+fn match_name(query: &str, name: &str) -> (MatchKind, f64) {
+    if query.trim().to_lowercase() == name.trim().to_lowercase() {
+        return (MatchKind::Exact, 1.0);
+    }
+
+    let query = normalize(query);
+    let name = normalize(name);
+    if query.is_empty() || name.is_empty() {
+        return (MatchKind::Fuzzy, 0.0);
+    }
+    if query == name || query.replace(' ', "") == name.replace(' ', "") {
+        return (MatchKind::Normalized, 0.9);
+    }
+    if name.starts_with(&format!("{} ", query)) {
+        return (MatchKind::Partial, 0.7);
+    }
+
+    let (query, name): (Vec<char>, Vec<char>) = (query.chars().collect(), name.chars().collect());
+    let distance = levenshtein(&query, &name);
+    let similarity = 1.0 - distance as f64 / query.len().max(name.len()) as f64;
+    (MatchKind::Fuzzy, 0.6 * similarity)
+}
+
+/// Lowercases `name`, replaces punctuation with spaces, collapses whitespace and drops a leading "the".
+pub(crate) fn normalize(name: &str) -> String {
+    let name = name
+        .to_lowercase()
+        .replace('&', " and ")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>();
+    let words: Vec<_> = name.split_whitespace().collect();
+    match words.split_first() {
+        Some((&"the", rest)) if !rest.is_empty() => rest.join(" "),
+        _ => words.join(" "),
+    }
+}
+
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_support::Fixtures;
+
+    fn candidate(id: u32, name: &str, alternate_names: &[&str], hits: usize) -> ArtistCandidate {
+        ArtistCandidate {
+            id,
+            name: String::from(name),
+            alternate_names: alternate_names.iter().map(|name| name.to_string()).collect(),
+            hits,
+            match_kind: MatchKind::Fuzzy,
+            score: 0.0,
+        }
+    }
+
+    fn scored(query: &str, mut candidates: Vec<ArtistCandidate>) -> Vec<ArtistCandidate> {
+        let total_hits = candidates.iter().map(|candidate| candidate.hits).sum();
+        for candidate in candidates.iter_mut() {
+            score(query, candidate, total_hits);
+        }
+        sort_candidates(&mut candidates);
+        candidates
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize("The Smiths"), "smiths");
+        assert_eq!(normalize("  Simon & Garfunkel "), "simon and garfunkel");
+        assert_eq!(normalize("AC/DC"), "ac dc");
+        assert_eq!(normalize("The The"), "the");
+        assert_eq!(normalize("?!"), "");
+    }
+
+    #[test]
+    fn computes_edit_distances() {
+        let chars = |word: &str| word.chars().collect::<Vec<_>>();
+        assert_eq!(levenshtein(&chars("kitten"), &chars("sitting")), 3);
+        assert_eq!(levenshtein(&chars(""), &chars("abc")), 3);
+        assert_eq!(levenshtein(&chars("beyoncé"), &chars("beyonce")), 1);
+        assert_eq!(levenshtein(&chars("same"), &chars("same")), 0);
+    }
+
+    #[test]
+    fn matches_names() {
+        assert_eq!(match_name("portishead", "Portishead"), (MatchKind::Exact, 1.0));
+        assert_eq!(match_name("Smiths", "The Smiths"), (MatchKind::Normalized, 0.9));
+        assert_eq!(match_name("acdc", "AC/DC"), (MatchKind::Normalized, 0.9));
+        assert_eq!(match_name("kendrick", "Kendrick Lamar"), (MatchKind::Partial, 0.7));
+
+        let (match_kind, score) = match_name("Kendrik Lamar", "Kendrick Lamar");
+        assert_eq!(match_kind, MatchKind::Fuzzy);
+        assert!(score > 0.5 && score < 0.6, "{}", score);
+        let (match_kind, score) = match_name("kendrick", "Kenny Chesney");
+        assert_eq!(match_kind, MatchKind::Fuzzy);
+        assert!(score < 0.3, "{}", score);
+        assert_eq!(match_name("!!!", "Chk Chk Chk"), (MatchKind::Fuzzy, 0.0));
+    }
+
+    #[test]
+    fn alternate_names_match_below_the_name() {
+        let candidates = scored(
+            "Kanye West",
+            vec![candidate(1, "Ye", &["Kanye West", "Yeezy"], 1), candidate(2, "Kanye West", &[], 1)],
+        );
+        assert_eq!(candidates[0].id, 2);
+        assert_eq!(candidates[0].match_kind, MatchKind::Exact);
+        assert_eq!(candidates[1].id, 1);
+        assert_eq!(candidates[1].match_kind, MatchKind::AlternateName);
+        assert!((candidates[1].score - (NAME_WEIGHT * 0.85 + FREQUENCY_WEIGHT * 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn frequent_artists_score_higher() {
+        let candidates = scored(
+            "kendrick",
+            vec![candidate(1, "Kendrick Lamar", &[], 1), candidate(2, "Kendrick Scott", &[], 9)],
+        );
+        assert_eq!(candidates[0].id, 2);
+        assert!((candidates[0].score - (NAME_WEIGHT * 0.7 + FREQUENCY_WEIGHT * 0.9)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ties_are_broken_by_hits_then_name() {
+        let mut candidates = vec![
+            ArtistCandidate {
+                score: 0.5,
+                ..candidate(1, "Beta", &[], 2)
+            },
+            ArtistCandidate {
+                score: 0.5,
+                ..candidate(2, "Alpha", &[], 2)
+            },
+            ArtistCandidate {
+                score: 0.5,
+                ..candidate(3, "Gamma", &[], 3)
+            },
+            ArtistCandidate {
+                score: 0.6,
+                ..candidate(4, "Delta", &[], 1)
+            },
+        ];
+        sort_candidates(&mut candidates);
+        let ids: Vec<_> = candidates.iter().map(|candidate| candidate.id).collect();
+        assert_eq!(ids, [4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_search_has_no_hit() {
+        let fixtures = Fixtures::new();
+        let query = [("q", String::from("nobody")), ("page", String::from("1"))];
+        fixtures.ok("/search", &query, json!({ "hits": [] }));
+
+        let result = fixtures.genius().resolve_artist("nobody", ResolveOptions::default()).await;
+        assert!(matches!(result, Err(GeniusError::NotFound { .. })), "{:?}", result);
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::GeniusError;
+
+/// Controls how failed Genius API requests are retried.
+///
+/// Rate limiting (429), server errors (5xx) and connection/timeout failures are retried
+/// with an exponential backoff: `base_delay * 2^(attempt - 1)`, capped at `max_delay`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of the delay (`0.0..=1.0`) that is randomly subtracted to spread out retries.
+    pub jitter: f64,
+    /// Waits for the duration given by the `Retry-After` header instead of the backoff delay.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn respect_retry_after(mut self, respect_retry_after: bool) -> Self {
+        self.respect_retry_after = respect_retry_after;
+        self
+    }
+
+    pub(crate) fn is_retryable(&self, err: &GeniusError) -> bool {
+        match err {
+            GeniusError::RateLimited { .. } | GeniusError::Server { .. } => true,
+            GeniusError::Transport(err) => err.is_timeout() || err.is_connect() || err.is_body(),
+            _ => false,
+        }
+    }
+
+    /// Delay to wait before the attempt following `attempt` (1-based).
+    pub(crate) fn delay(&self, attempt: u32, err: &GeniusError) -> Duration {
+        if let GeniusError::RateLimited {
+            retry_after: Some(retry_after),
+        } = err
+        {
+            if self.respect_retry_after {
+                return *retry_after;
+            }
+        }
+
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_delay);
+        // The field is public, so it may not have gone through the clamping setter.
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter > 0.0 {
+            delay.mul_f64(1.0 - rand::thread_rng().gen_range(0.0..=jitter))
+        } else {
+            delay
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_clamps_jitter_set_through_the_field() {
+        let err = GeniusError::Server {
+            status: 503,
+            message: String::new(),
+        };
+        for jitter in [1.5, -1.0, f64::NAN] {
+            let policy = RetryPolicy {
+                jitter,
+                ..Default::default()
+            };
+            assert!(policy.delay(1, &err) <= policy.base_delay);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use net::{
+    client::{HttpRequest, HttpResponse, NetError},
+    transport::{Fixture, HttpTransport, ReplayTransport},
+};
+use reqwest::Method;
+use serde_json::Value;
+use tempfile::TempDir;
+
+use crate::{builder::GeniusBuilder, constants::BASE_URL, genius::Genius, retry::RetryPolicy};
+
+/// Replay fixtures written at test time, for responses that are shorter to describe inline than to record.
+pub(crate) struct Fixtures {
+    dir: TempDir,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+    delays: Arc<Mutex<HashMap<String, Duration>>>,
+}
+
+impl Fixtures {
+    pub(crate) fn new() -> Self {
+        Self {
+            dir: tempfile::tempdir().unwrap(),
+            requests: Arc::default(),
+            delays: Arc::default(),
+        }
+    }
+
+    /// Answers `GET <path>?<query>` with a successful response wrapping `response`.
+    pub(crate) fn ok(&self, path: &str, query: &[(&str, String)], response: Value) -> &Self {
+        let body = serde_json::json!({ "meta": { "status": 200 }, "response": response });
+        self.respond(path, query, 200, &[], &body.to_string())
+    }
+
+    /// Answers `GET <path>?<query>` with a raw status, headers and body.
+    pub(crate) fn respond(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        status: u16,
+        headers: &[(&str, &str)],
+        body: &str,
+    ) -> &Self {
+        let request = request(path, query);
+        let fixture = Fixture {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            query: request.query.clone(),
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: body.to_string(),
+        };
+        let path = self.dir.path().join(request.fingerprint()).with_extension("json");
+        std::fs::write(path, serde_json::to_string(&fixture).unwrap()).unwrap();
+        self
+    }
+
+    /// Delays the response to `GET <path>?<query>` by `delay`, e.g. to complete concurrent requests out of order.
+    pub(crate) fn delay(&self, path: &str, query: &[(&str, String)], delay: Duration) -> &Self {
+        self.delays.lock().unwrap().insert(request(path, query).fingerprint(), delay);
+        self
+    }
+
+    /// A client replaying the fixtures. Failed requests are not retried.
+    pub(crate) fn genius(&self) -> Genius {
+        let transport = FixtureTransport {
+            replay: ReplayTransport::new(self.dir.path()),
+            requests: Arc::clone(&self.requests),
+            delays: Arc::clone(&self.delays),
+        };
+        GeniusBuilder::new()
+            .token("replay")
+            .retry_policy(RetryPolicy::none())
+            .transport(Arc::new(transport))
+            .build()
+            .unwrap()
+    }
+
+    /// Requests sent by the clients, including the ones without a fixture.
+    pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn request(path: &str, query: &[(&str, String)]) -> HttpRequest {
+    HttpRequest::new(Method::GET, format!("{}{}", BASE_URL, path)).query(query)
+}
+
+/// Replays fixtures, logging every request and delaying the responses that were asked to be.
+struct FixtureTransport {
+    replay: ReplayTransport,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+    delays: Arc<Mutex<HashMap<String, Duration>>>,
+}
+
+#[async_trait]
+impl HttpTransport for FixtureTransport {
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NetError> {
+        self.requests.lock().unwrap().push(request.clone());
+        let delay = self.delays.lock().unwrap().get(&request.fingerprint()).copied();
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+        self.replay.send(request).await
+    }
+}
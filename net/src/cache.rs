@@ -0,0 +1,372 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+const CACHE_DIR: &str = "lyri";
+const ENTRY_EXTENSION: &str = "json";
+const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_MAX_BYTES: u64 = 1024 * 1024 * 1024;
+/// Share of `max_bytes` kept when a write makes the cache too large, so that a full cache is not scanned on every write.
+const EVICTION_TARGET_PERCENT: u64 = 90;
+
+/// A response stored on disk, keyed by URL and query.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CacheEntry {
+    /// URL of the request, with its sorted query as returned by [`HttpCache::normalize`].
+    pub url: String,
+    /// Seconds since the Unix epoch at which the entry was stored or last revalidated.
+    pub stored_at: u64,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub status: u16,
+    pub body: String,
+}
+
+impl CacheEntry {
+    pub fn new(url: String, status: u16, body: String, etag: Option<String>, last_modified: Option<String>) -> Self {
+        Self {
+            url,
+            stored_at: now(),
+            etag,
+            last_modified,
+            status,
+            body,
+        }
+    }
+
+    /// Whether the server can be asked if the entry is still up to date.
+    pub fn can_revalidate(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+/// Content cache shared by the Genius API client and the lyrics scraper.
+///
+/// Every entry is a JSON file named after a hash of its URL and query. Entries older than `ttl`
+/// are revalidated with `ETag`/`Last-Modified` when possible, and the least recently written entries
+/// are evicted once the cache grows over `max_bytes`.
+///
+/// The cache does blocking file I/O: async code should call it from a blocking task.
+pub struct HttpCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_bytes: Option<u64>,
+    /// Total size of the entries, counted on the first write and kept up to date since.
+    /// The lock also serializes writes.
+    size: Mutex<Option<u64>>,
+}
+
+impl HttpCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: DEFAULT_TTL,
+            max_bytes: Some(DEFAULT_MAX_BYTES),
+            size: Mutex::new(None),
+        }
+    }
+
+    /// Cache in `$XDG_CACHE_HOME/lyri` (or `~/.cache/lyri`), if a cache directory can be determined.
+    pub fn user_default() -> Option<Self> {
+        cache_dir().map(|dir| Self::new(dir.join(CACHE_DIR)))
+    }
+
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Maximum size of the cache. `None` disables eviction.
+    pub fn max_bytes(mut self, max_bytes: Option<u64>) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// URL of a request with its query. Query parameters are sorted so their order does not matter.
+    pub fn normalize(url: &str, query: &[(String, String)]) -> String {
+        let mut query = query.to_vec();
+        query.sort();
+        let mut normalized = String::from(url);
+        for (i, (name, value)) in query.iter().enumerate() {
+            normalized.push(if i == 0 { '?' } else { '&' });
+            normalized.push_str(name);
+            normalized.push('=');
+            normalized.push_str(value);
+        }
+        normalized
+    }
+
+    /// Key of a request: a hash of its [normalized](Self::normalize) URL.
+    pub fn key(url: &str, query: &[(String, String)]) -> String {
+        format!("{:016x}", fnv1a(Self::normalize(url, query).as_bytes()))
+    }
+
+    /// The entry stored under `key` for the normalized `url`. Entries of other URLs whose hash collides are ignored.
+    pub fn get(&self, key: &str, url: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        match serde_json::from_str::<CacheEntry>(&content) {
+            Ok(entry) if entry.url == url => Some(entry),
+            Ok(entry) => {
+                debug!("Cache entry `{}` holds `{}`, not `{}`", key, entry.url, url);
+                None
+            }
+            Err(err) => {
+                warn!("Ignoring corrupted cache entry `{}`: {}", key, err);
+                None
+            }
+        }
+    }
+
+    pub fn is_fresh(&self, entry: &CacheEntry) -> bool {
+        now().saturating_sub(entry.stored_at) < self.ttl.as_secs()
+    }
+
+    pub fn put(&self, key: &str, entry: &CacheEntry) -> io::Result<()> {
+        let mut size = self.lock_size();
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string(entry).map_err(io::Error::other)?;
+        let path = self.entry_path(key);
+        let replaced = match fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err),
+        };
+        fs::write(&path, &content)?;
+
+        let Some(max_bytes) = self.max_bytes else {
+            return Ok(());
+        };
+        let total = match *size {
+            Some(total) => total.saturating_sub(replaced) + content.len() as u64,
+            None => self.entry_files()?.iter().map(|file| file.bytes).sum(),
+        };
+        *size = Some(total);
+        if total > max_bytes {
+            self.evict(&mut size, max_bytes / 100 * EVICTION_TARGET_PERCENT, false)?;
+        }
+        Ok(())
+    }
+
+    /// Marks an entry as fresh again after the server confirmed it did not change.
+    pub fn touch(&self, key: &str, mut entry: CacheEntry) -> io::Result<CacheEntry> {
+        entry.stored_at = now();
+        self.put(key, &entry)?;
+        Ok(entry)
+    }
+
+    pub fn stats(&self) -> io::Result<CacheStats> {
+        let mut stats = CacheStats::default();
+        for file in self.entry_files()? {
+            stats.entries += 1;
+            stats.bytes += file.bytes;
+            if self.is_expired(&file) {
+                stats.expired += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Removes expired entries, then the least recently written ones until the cache fits in `max_bytes`.
+    pub fn prune(&self) -> io::Result<PruneReport> {
+        let mut size = self.lock_size();
+        self.evict(&mut size, self.max_bytes.unwrap_or(u64::MAX), true)
+    }
+
+    /// Removes every entry and returns how many there were.
+    pub fn clear(&self) -> io::Result<usize> {
+        let mut size = self.lock_size();
+        let files = self.entry_files()?;
+        for file in &files {
+            remove_if_exists(&file.path)?;
+        }
+        *size = Some(0);
+        Ok(files.len())
+    }
+
+    /// Removes the least recently written entries until the cache fits in `max_bytes`, and updates `size`.
+    /// Entries are ordered by modification time, which is when they were stored or last revalidated.
+    fn evict(&self, size: &mut Option<u64>, max_bytes: u64, remove_expired: bool) -> io::Result<PruneReport> {
+        let mut report = PruneReport::default();
+        let mut files = vec![];
+        for file in self.entry_files()? {
+            if remove_expired && self.is_expired(&file) {
+                remove_if_exists(&file.path)?;
+                report.removed += 1;
+                report.freed_bytes += file.bytes;
+            } else {
+                files.push(file);
+            }
+        }
+
+        let mut total: u64 = files.iter().map(|file| file.bytes).sum();
+        files.sort_by_key(|file| file.modified);
+        for file in files {
+            if total <= max_bytes {
+                break;
+            }
+            remove_if_exists(&file.path)?;
+            total -= file.bytes;
+            report.removed += 1;
+            report.freed_bytes += file.bytes;
+        }
+        *size = Some(total);
+        Ok(report)
+    }
+
+    fn lock_size(&self) -> MutexGuard<'_, Option<u64>> {
+        self.size.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_expired(&self, file: &EntryFile) -> bool {
+        SystemTime::now()
+            .duration_since(file.modified)
+            .is_ok_and(|age| age >= self.ttl)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(key).with_extension(ENTRY_EXTENSION)
+    }
+
+    fn entry_files(&self) -> io::Result<Vec<EntryFile>> {
+        let read_dir = match fs::read_dir(&self.dir) {
+            Ok(read_dir) => read_dir,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+
+        let mut files = vec![];
+        for dir_entry in read_dir {
+            let dir_entry = dir_entry?;
+            let path = dir_entry.path();
+            if path.extension().is_some_and(|extension| extension == ENTRY_EXTENSION) {
+                let metadata = dir_entry.metadata()?;
+                files.push(EntryFile {
+                    path,
+                    bytes: metadata.len(),
+                    modified: metadata.modified().unwrap_or(UNIX_EPOCH),
+                });
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// An entry as seen from the directory listing, without reading it.
+struct EntryFile {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn cache_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// 64-bit FNV-1a, used because it is stable across Rust versions, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    /// A cache in a directory removed when the returned guard is dropped.
+    fn temp_cache() -> (HttpCache, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        (HttpCache::new(dir.path()), dir)
+    }
+
+    fn entry(url: &str, body_len: usize) -> CacheEntry {
+        CacheEntry::new(String::from(url), 200, "x".repeat(body_len), None, None)
+    }
+
+    fn set_age(cache: &HttpCache, key: &str, age: Duration) {
+        let file = File::options().write(true).open(cache.entry_path(key)).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    #[test]
+    fn get_ignores_entries_of_other_urls() {
+        let (cache, _dir) = temp_cache();
+        cache.put("0123456789abcdef", &entry("https://genius.com/a", 10)).unwrap();
+
+        assert!(cache.get("0123456789abcdef", "https://genius.com/a").is_some());
+        assert!(cache.get("0123456789abcdef", "https://genius.com/b").is_none());
+    }
+
+    #[test]
+    fn put_evicts_the_least_recently_written_entries() {
+        let (cache, _dir) = temp_cache();
+        let (first, second) = (entry("https://genius.com/1", 1000), entry("https://genius.com/2", 1000));
+        cache.put("1", &first).unwrap();
+        cache.put("2", &second).unwrap();
+        set_age(&cache, "1", Duration::from_secs(60));
+        set_age(&cache, "2", Duration::from_secs(120));
+        let entry_bytes = cache.stats().unwrap().bytes / 2;
+
+        let cache = cache.max_bytes(Some(entry_bytes * 5 / 2));
+        cache.put("3", &entry("https://genius.com/3", 1000)).unwrap();
+        assert!(cache.get("1", &first.url).is_some());
+        assert!(cache.get("2", &second.url).is_none());
+        assert_eq!(cache.stats().unwrap().entries, 2);
+    }
+
+    #[test]
+    fn prune_removes_expired_entries() {
+        let (cache, _dir) = temp_cache();
+        let cache = cache.ttl(Duration::from_secs(60));
+        cache.put("1", &entry("https://genius.com/1", 10)).unwrap();
+        cache.put("2", &entry("https://genius.com/2", 10)).unwrap();
+        set_age(&cache, "2", Duration::from_secs(120));
+
+        assert_eq!(cache.stats().unwrap().expired, 1);
+        assert_eq!(cache.prune().unwrap().removed, 1);
+        assert!(cache.get("1", "https://genius.com/1").is_some());
+    }
+}
@@ -0,0 +1,277 @@
+use std::{fmt, path::PathBuf, sync::Arc, time::Duration};
+
+use log::{debug, warn};
+use reqwest::{
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    Client, Method, StatusCode,
+};
+
+use crate::{
+    cache::{CacheEntry, HttpCache},
+    rate_limit::RateLimiter,
+    transport::{HttpTransport, ReqwestTransport},
+};
+
+/// A request sent through [`HttpClient`].
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    /// Whether the response may be served from and stored in the cache. Only `GET` requests are cached.
+    pub cacheable: bool,
+    /// Overrides the timeout of the transport's client.
+    pub timeout: Option<Duration>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            query: vec![],
+            headers: vec![],
+            cacheable: false,
+            timeout: None,
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::GET, url).cacheable(true)
+    }
+
+    pub fn query(mut self, query: &[(&str, String)]) -> Self {
+        self.query
+            .extend(query.iter().map(|(name, value)| (name.to_string(), value.clone())));
+        self
+    }
+
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn bearer_auth(self, token: &str) -> Self {
+        self.header("Authorization", format!("Bearer {}", token))
+    }
+
+    pub fn cacheable(mut self, cacheable: bool) -> Self {
+        self.cacheable = cacheable;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Identifies the request by method, URL and query, ignoring headers.
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "{}-{}",
+            self.method.as_str().to_lowercase(),
+            HttpCache::key(&self.url, &self.query)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    /// Whether the body was served from the cache, possibly after a successful revalidation.
+    pub from_cache: bool,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Value of the first header named `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn from_entry(entry: CacheEntry) -> Self {
+        Self {
+            status: entry.status,
+            headers: vec![],
+            body: entry.body,
+            from_cache: true,
+        }
+    }
+}
+
+/// HTTP client with an optional on-disk cache, shared by the Genius API client and the scraper.
+#[derive(Clone)]
+pub struct HttpClient {
+    transport: Arc<dyn HttpTransport>,
+    cache: Option<Arc<HttpCache>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    offline: bool,
+}
+
+impl HttpClient {
+    pub fn new(reqwest: Client) -> Self {
+        Self::with_transport(Arc::new(ReqwestTransport::new(reqwest)))
+    }
+
+    /// Sends requests through `transport`, e.g. to record or replay fixtures.
+    pub fn with_transport(transport: Arc<dyn HttpTransport>) -> Self {
+        Self {
+            transport,
+            cache: None,
+            rate_limiter: None,
+            offline: false,
+        }
+    }
+
+    pub fn cache(mut self, cache: Arc<HttpCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Spaces out requests that reach the transport. Responses served from the cache are not limited.
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
+    /// Serves cacheable requests from the cache only, even if entries are stale, and fails on a miss.
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub async fn send(&self, request: HttpRequest) -> Result<HttpResponse, NetError> {
+        let cache = match &self.cache {
+            Some(cache) if request.cacheable && request.method == Method::GET => cache,
+            _ if self.offline => return Err(NetError::Offline { url: request.url }),
+            _ => return self.send_uncached(&request, None).await,
+        };
+
+        let url = HttpCache::normalize(&request.url, &request.query);
+        let key = HttpCache::key(&request.url, &request.query);
+        let cached = {
+            let (key, url) = (key.clone(), url.clone());
+            blocking(cache, move |cache| cache.get(&key, &url)).await
+        };
+        match cached {
+            Some(entry) if self.offline || cache.is_fresh(&entry) => {
+                debug!("Serving `{}` from cache", request.url);
+                return Ok(HttpResponse::from_entry(entry));
+            }
+            None if self.offline => return Err(NetError::Offline { url: request.url }),
+            _ => {}
+        }
+
+        let cached = cached.filter(CacheEntry::can_revalidate);
+        let response = self.send_uncached(&request, cached.as_ref()).await?;
+        match cached {
+            Some(entry) if response.status == StatusCode::NOT_MODIFIED.as_u16() => {
+                debug!("Revalidated `{}`", request.url);
+                let touched = {
+                    let entry = entry.clone();
+                    blocking(cache, move |cache| cache.touch(&key, entry)).await
+                };
+                let entry = touched.unwrap_or_else(|err| {
+                    warn!("Could not update cache entry for `{}`: {}", request.url, err);
+                    entry
+                });
+                Ok(HttpResponse::from_entry(entry))
+            }
+            _ => {
+                if response.is_success() {
+                    let entry = CacheEntry::new(
+                        url,
+                        response.status,
+                        response.body.clone(),
+                        response.header(ETAG.as_str()).map(String::from),
+                        response.header(LAST_MODIFIED.as_str()).map(String::from),
+                    );
+                    if let Err(err) = blocking(cache, move |cache| cache.put(&key, &entry)).await {
+                        warn!("Could not cache `{}`: {}", request.url, err);
+                    }
+                }
+                Ok(response)
+            }
+        }
+    }
+
+    async fn send_uncached(
+        &self,
+        request: &HttpRequest,
+        revalidate: Option<&CacheEntry>,
+    ) -> Result<HttpResponse, NetError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(&request.url).await;
+        }
+        match revalidate {
+            Some(entry) => {
+                let mut request = request.clone();
+                if let Some(etag) = &entry.etag {
+                    request = request.header(IF_NONE_MATCH.as_str(), etag);
+                }
+                if let Some(last_modified) = &entry.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE.as_str(), last_modified);
+                }
+                self.transport.send(&request).await
+            }
+            None => self.transport.send(request).await,
+        }
+    }
+}
+
+/// Runs `f` on the blocking thread pool, since the cache reads and writes files synchronously.
+async fn blocking<T: Send + 'static>(
+    cache: &Arc<HttpCache>,
+    f: impl FnOnce(&HttpCache) -> T + Send + 'static,
+) -> T {
+    let cache = Arc::clone(cache);
+    tokio::task::spawn_blocking(move || f(&cache))
+        .await
+        .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()))
+}
+
+#[derive(Debug)]
+pub enum NetError {
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// Offline mode is enabled and the response is not cached.
+    Offline { url: String },
+    /// No fixture has been recorded for the request.
+    MissingFixture { url: String, path: PathBuf },
+    InvalidFixture { path: PathBuf, message: String },
+}
+
+impl fmt::Display for NetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetError::Transport(err) => write!(f, "Transport error: {}", err),
+            NetError::Offline { url } => write!(f, "`{}` is not cached and offline mode is enabled", url),
+            NetError::MissingFixture { url, path } => write!(f, "No fixture for `{}` (expected {:?})", url, path),
+            NetError::InvalidFixture { path, message } => write!(f, "Invalid fixture {:?}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for NetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NetError::Transport(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for NetError {
+    fn from(err: reqwest::Error) -> Self {
+        NetError::Transport(err)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod cache;
+pub mod client;
+pub mod rate_limit;
+pub mod transport;
@@ -0,0 +1,105 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::Url;
+
+/// Spaces requests at least `interval` apart, across every task sharing the limiter.
+///
+/// Concurrent callers are queued: each one reserves the next free slot and sleeps until then.
+/// A per-host limiter keeps one queue per host, so requests to different hosts do not wait for each other.
+pub struct RateLimiter {
+    interval: Duration,
+    per_host: bool,
+    next_slots: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            per_host: false,
+            next_slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Allows at most `requests` requests per second. `0` is treated as `1`.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(Duration::from_secs(1) / requests.max(1))
+    }
+
+    /// Applies the interval to every host separately.
+    pub fn per_host(mut self, per_host: bool) -> Self {
+        self.per_host = per_host;
+        self
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Waits until the caller may send a request to `url`.
+    pub async fn acquire(&self, url: &str) {
+        let key = if self.per_host {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(String::from))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let delay = {
+            let mut next_slots = self.next_slots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let now = Instant::now();
+            let next_slot = next_slots.entry(key).or_insert(now);
+            let slot = (*next_slot).max(now);
+            *next_slot = slot + self.interval;
+            slot - now
+        };
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(200);
+
+    /// Time `acquire` took for each URL, acquired in order.
+    async fn waits(limiter: &RateLimiter, urls: &[&str]) -> Vec<Duration> {
+        let mut waits = vec![];
+        for url in urls {
+            let start = Instant::now();
+            limiter.acquire(url).await;
+            waits.push(start.elapsed());
+        }
+        waits
+    }
+
+    #[tokio::test]
+    async fn spaces_requests_to_the_same_host() {
+        let limiter = RateLimiter::new(INTERVAL).per_host(true);
+        let waits = waits(&limiter, &["https://genius.com/a", "https://genius.com/b"]).await;
+        assert!(waits[0] < INTERVAL / 2, "{:?}", waits);
+        assert!(waits[1] >= INTERVAL - Duration::from_millis(10), "{:?}", waits);
+    }
+
+    #[tokio::test]
+    async fn does_not_space_requests_to_other_hosts() {
+        let limiter = RateLimiter::new(INTERVAL).per_host(true);
+        let waits = waits(&limiter, &["https://genius.com/a", "https://api.genius.com/a"]).await;
+        assert!(waits.iter().all(|wait| *wait < INTERVAL / 2), "{:?}", waits);
+    }
+
+    #[tokio::test]
+    async fn spaces_every_request_without_per_host() {
+        let limiter = RateLimiter::new(INTERVAL);
+        let waits = waits(&limiter, &["https://genius.com/a", "https://api.genius.com/a"]).await;
+        assert!(waits[1] >= INTERVAL - Duration::from_millis(10), "{:?}", waits);
+    }
+}
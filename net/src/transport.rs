@@ -0,0 +1,150 @@
+use std::{fs, io, path::PathBuf};
+
+use async_trait::async_trait;
+use log::{debug, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::client::{HttpRequest, HttpResponse, NetError};
+
+const FIXTURE_EXTENSION: &str = "json";
+
+/// Sends requests over the wire. [`HttpClient`](crate::client::HttpClient) adds caching on top of it.
+#[async_trait]
+pub trait HttpTransport: Send + Sync {
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NetError>;
+}
+
+/// Sends requests with `reqwest`.
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReqwestTransport {
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NetError> {
+        let mut builder = self
+            .client
+            .request(request.method.clone(), &request.url)
+            .query(&request.query);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(timeout) = request.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response.text().await?;
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+            from_cache: false,
+        })
+    }
+}
+
+/// A recorded request/response pair, stored as `<fingerprint>.json` in a fixture directory.
+///
+/// Request headers are not recorded, so fixtures never contain access tokens.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Fixture {
+    pub method: String,
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// Forwards requests to another transport and writes every exchange to a fixture directory.
+pub struct RecordingTransport<T> {
+    inner: T,
+    dir: PathBuf,
+}
+
+impl<T: HttpTransport> RecordingTransport<T> {
+    pub fn new(inner: T, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    fn record(&self, request: &HttpRequest, response: &HttpResponse) -> io::Result<()> {
+        let fixture = Fixture {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            query: request.query.clone(),
+            status: response.status,
+            headers: response.headers.clone(),
+            body: response.body.clone(),
+        };
+        fs::create_dir_all(&self.dir)?;
+        let content = serde_json::to_string_pretty(&fixture).map_err(io::Error::other)?;
+        fs::write(fixture_path(&self.dir, request), content)
+    }
+}
+
+#[async_trait]
+impl<T: HttpTransport> HttpTransport for RecordingTransport<T> {
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NetError> {
+        let response = self.inner.send(request).await?;
+        if let Err(err) = self.record(request, &response) {
+            warn!("Could not record `{}`: {}", request.url, err);
+        }
+        Ok(response)
+    }
+}
+
+/// Serves responses from a fixture directory written by [`RecordingTransport`], without network access.
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ReplayTransport {
+    async fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NetError> {
+        let path = fixture_path(&self.dir, request);
+        let content = fs::read_to_string(&path).map_err(|_| NetError::MissingFixture {
+            url: request.url.clone(),
+            path: path.clone(),
+        })?;
+        let fixture: Fixture = serde_json::from_str(&content).map_err(|err| NetError::InvalidFixture {
+            path,
+            message: err.to_string(),
+        })?;
+        debug!("Replaying `{}`", request.url);
+
+        Ok(HttpResponse {
+            status: fixture.status,
+            headers: fixture.headers,
+            body: fixture.body,
+            from_cache: false,
+        })
+    }
+}
+
+fn fixture_path(dir: &std::path::Path, request: &HttpRequest) -> PathBuf {
+    dir.join(request.fingerprint()).with_extension(FIXTURE_EXTENSION)
+}
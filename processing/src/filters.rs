@@ -65,6 +65,7 @@ impl PostProcessor for TitleSanitizer {
     }
 }
 
+#[derive(Clone)]
 pub struct FilterOptions {
     pub include_features: Option<bool>,
     pub antipattern: Option<String>,
@@ -0,0 +1,131 @@
+use genius::model::page::{AlbumTrack, AlbumTracklist};
+use once_cell::sync::Lazy;
+use scraper::{ElementRef, Html, Selector};
+
+const LYRICS_SUFFIX: &str = "Lyrics";
+
+// Album pages have been served with two layouts: `chart_row` lists and styled `AlbumTracklist` components.
+static TRACK_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"div.chart_row, div[class*="AlbumTracklist__Track-"]"#).unwrap());
+static TRACK_NUMBER_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(r#".chart_row-number_container-number, [class*="AlbumTracklist__TrackNumber"]"#).unwrap()
+});
+static TRACK_LINK_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("a[href]").unwrap());
+static TRACK_TITLE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("h3").unwrap());
+static ALBUM_PATH_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"meta[name="newrelic-resource-path"]"#).unwrap());
+static ALBUM_NAME_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse("h1").unwrap());
+static ALBUM_ARTIST_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(
+        r#".header_with_cover_art-primary_info-primary_artist, a[class*="HeaderArtistAndTracklist__Artist"]"#,
+    )
+    .unwrap()
+});
+
+/// Reads the tracklist of the album page found at `url`.
+///
+/// Returns `None` if the page lists no tracks, e.g. because it is not an album page.
+pub fn parse_album(html: &str, url: &str) -> Option<AlbumTracklist> {
+    let document = Html::parse_document(html);
+    let tracks: Vec<AlbumTrack> = document.select(&TRACK_SELECTOR).filter_map(parse_track).collect();
+    if tracks.is_empty() {
+        return None;
+    }
+
+    let album_id = document
+        .select(&ALBUM_PATH_SELECTOR)
+        .filter_map(|meta| meta.value().attr("content"))
+        .find_map(|path| path.strip_prefix("/albums/")?.parse::<u32>().ok());
+    let name = document.select(&ALBUM_NAME_SELECTOR).next().map(text).unwrap_or_default();
+    let artist = document.select(&ALBUM_ARTIST_SELECTOR).next().map(text);
+
+    Some(AlbumTracklist {
+        album_id,
+        name,
+        artist,
+        url: String::from(url),
+        tracks,
+    })
+}
+
+fn parse_track(row: ElementRef) -> Option<AlbumTrack> {
+    let title = row.select(&TRACK_TITLE_SELECTOR).next().map(text)?;
+    let title = title.strip_suffix(LYRICS_SUFFIX).unwrap_or(&title).trim().to_string();
+    let number = row
+        .select(&TRACK_NUMBER_SELECTOR)
+        .next()
+        .and_then(|number| text(number).trim_end_matches('.').parse().ok());
+    let url = row
+        .select(&TRACK_LINK_SELECTOR)
+        .find_map(|link| link.value().attr("href"))
+        .map(String::from);
+
+    Some(AlbumTrack { number, title, url })
+}
+
+/// The text of `element` with whitespace collapsed.
+fn text(element: ElementRef) -> String {
+    element.text().collect::<String>().split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    /// Saved album pages in both layouts, and a song page.
+    fn parse_fixture(name: &str) -> Option<AlbumTracklist> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/album").join(name);
+        parse_album(&fs::read_to_string(path).unwrap(), "https://genius.com/albums/Portishead/Album")
+    }
+
+    fn tracks(tracklist: &AlbumTracklist) -> Vec<(Option<u32>, &str, Option<&str>)> {
+        tracklist
+            .tracks
+            .iter()
+            .map(|track| (track.number, track.title.as_str(), track.url.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_chart_row_pages() {
+        let tracklist = parse_fixture("chart_row.html").unwrap();
+        assert_eq!(tracklist.album_id, Some(11913));
+        assert_eq!(tracklist.name, "Portishead");
+        assert_eq!(tracklist.artist.as_deref(), Some("Portishead"));
+        assert_eq!(tracklist.url, "https://genius.com/albums/Portishead/Album");
+        assert_eq!(
+            tracks(&tracklist),
+            [
+                (Some(1), "Cowboys", Some("https://genius.com/Portishead-cowboys-lyrics")),
+                (Some(2), "All Mine", Some("https://genius.com/Portishead-all-mine-lyrics")),
+                (Some(3), "Undenied", Some("https://genius.com/Portishead-undenied-lyrics")),
+                (None, "Roads (Live at Roseland)", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_album_tracklist_pages() {
+        let tracklist = parse_fixture("album_tracklist.html").unwrap();
+        assert_eq!(tracklist.album_id, Some(11914));
+        assert_eq!(tracklist.name, "Third");
+        assert_eq!(tracklist.artist.as_deref(), Some("Portishead"));
+        // Unnumbered tracks keep their place in the list.
+        assert_eq!(
+            tracks(&tracklist),
+            [
+                (Some(1), "Silence", Some("https://genius.com/Portishead-silence-lyrics")),
+                (Some(2), "Hunter", Some("https://genius.com/Portishead-hunter-lyrics")),
+                (None, "Nylon Smile (Demo)", Some("https://genius.com/Portishead-nylon-smile-demo-lyrics")),
+                (Some(3), "The Rip", Some("https://genius.com/Portishead-the-rip-lyrics")),
+            ]
+        );
+    }
+
+    #[test]
+    fn pages_without_tracks_are_not_albums() {
+        assert!(parse_fixture("song_page.html").is_none());
+    }
+}
@@ -0,0 +1,362 @@
+use std::{fmt, ops::Range};
+
+use genius::model::lyrics::AnnotatedSpan;
+
+use crate::render::ScrapedLyrics;
+
+const RECOMMENDATIONS: &str = "You might also like";
+const EMBED: &str = "Embed";
+const CONTRIBUTORS: &str = "Contributor";
+const LYRICS: &str = "Lyrics";
+const TRANSLATIONS: &str = "Translations";
+
+/// Language names listed by the translation picker of Genius pages.
+const LANGUAGES: [&str; 36] = [
+    "English",
+    "Español",
+    "Français",
+    "Deutsch",
+    "Português",
+    "Italiano",
+    "Nederlands",
+    "Polski",
+    "Русский (Russian)",
+    "Русский",
+    "Українська",
+    "Türkçe",
+    "Ελληνικά",
+    "Svenska",
+    "Dansk",
+    "Norsk",
+    "Suomi",
+    "Magyar",
+    "Română",
+    "Čeština",
+    "Česky",
+    "Slovenčina",
+    "Srpski",
+    "Hrvatski",
+    "Azərbaycanca",
+    "Català",
+    "Bahasa Indonesia",
+    "Tiếng Việt",
+    "العربية",
+    "فارسی",
+    "עברית",
+    "हिन्दी",
+    "日本語",
+    "한국어",
+    "中文",
+    "简体中文",
+];
+
+/// A kind of boilerplate Genius injects in lyrics containers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Artifact {
+    /// `57 ContributorsTranslationsEspañolWonderwall Lyrics` at the top of the lyrics.
+    ContributorsHeader,
+    /// `You might also like` lines introducing recommended songs.
+    Recommendations,
+    /// `123Embed` at the end of the lyrics, on its own line or glued to the last line.
+    EmbedTrailer,
+    /// `Translations` followed by a list of languages, at the top of the lyrics or below the contributors header.
+    TranslationPicker,
+}
+
+impl fmt::Display for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Artifact::ContributorsHeader => write!(f, "contributors header"),
+            Artifact::Recommendations => write!(f, "recommendations"),
+            Artifact::EmbedTrailer => write!(f, "embed trailer"),
+            Artifact::TranslationPicker => write!(f, "translation picker"),
+        }
+    }
+}
+
+/// Text removed from the lyrics.
+#[derive(Debug, Clone)]
+pub struct Removal {
+    pub artifact: Artifact,
+    pub text: String,
+}
+
+/// What [`Cleaner::clean`] removed from a song's lyrics.
+#[derive(Debug, Clone, Default)]
+pub struct CleanReport {
+    pub removals: Vec<Removal>,
+}
+
+impl CleanReport {
+    pub fn is_empty(&self) -> bool {
+        self.removals.is_empty()
+    }
+}
+
+/// Strips Genius page boilerplate from scraped lyrics, keeping annotated spans aligned with the text.
+#[derive(Debug, Clone, Copy)]
+pub struct Cleaner {
+    enabled: bool,
+}
+
+impl Default for Cleaner {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl Cleaner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// When disabled, lyrics are returned as scraped.
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Removes the artifacts found in `lyrics` and lists them in [`ScrapedLyrics::cleaning`].
+    pub fn clean(&self, lyrics: ScrapedLyrics) -> ScrapedLyrics {
+        if !self.enabled {
+            return lyrics;
+        }
+
+        let ranges = find_artifacts(&lyrics.text);
+        let report = CleanReport {
+            removals: ranges
+                .iter()
+                .map(|(range, artifact)| Removal {
+                    artifact: *artifact,
+                    text: lyrics.text[range.clone()].trim().to_string(),
+                })
+                .collect(),
+        };
+        if ranges.is_empty() {
+            return lyrics;
+        }
+
+        let ranges: Vec<_> = ranges.into_iter().map(|(range, _)| range).collect();
+        let mut text = String::with_capacity(lyrics.text.len());
+        let mut kept = 0;
+        for range in &ranges {
+            text.push_str(&lyrics.text[kept..range.start]);
+            kept = range.end;
+        }
+        text.push_str(&lyrics.text[kept..]);
+
+        let annotations = lyrics
+            .annotations
+            .iter()
+            .filter_map(|span| {
+                let start = shift(char_to_byte(&lyrics.text, span.start), &ranges);
+                let end = shift(char_to_byte(&lyrics.text, span.end), &ranges);
+                (start < end).then(|| AnnotatedSpan {
+                    start: text[..start].chars().count(),
+                    end: text[..end].chars().count(),
+                    fragment: text[start..end].to_string(),
+                    referent_id: span.referent_id,
+                })
+            })
+            .collect();
+
+        ScrapedLyrics {
+            text,
+            annotations,
+            cleaning: report,
+            synced: lyrics.synced,
+        }
+    }
+}
+
+/// Byte ranges of the artifacts found in `text`. Ranges of whole lines include their line break.
+fn find_artifacts(text: &str) -> Vec<(Range<usize>, Artifact)> {
+    let lines = lines_with_offsets(text);
+    let mut artifacts = vec![];
+    // Index of the first line that is not part of the contributors header.
+    let mut header_lines = 0;
+
+    if let Some(&(offset, line)) = lines.first() {
+        if is_contributors_header(line) {
+            header_lines = 1;
+            let end = match line.find(LYRICS) {
+                Some(index) if !line[index + LYRICS.len()..].trim().is_empty() => offset + index + LYRICS.len(),
+                _ => line_end(text, offset, line),
+            };
+            artifacts.push((offset..end, Artifact::ContributorsHeader));
+        }
+    }
+
+    let mut picker: Option<usize> = None;
+    for (i, &(offset, line)) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        // Lyrics may mention translations too: only the picker of the page header is removed.
+        if i <= header_lines && trimmed == TRANSLATIONS && lines.get(i + 1).is_some_and(|(_, next)| is_language(next)) {
+            picker = Some(offset);
+            continue;
+        }
+        if let Some(start) = picker {
+            if is_language(line) {
+                continue;
+            }
+            artifacts.push((start..offset, Artifact::TranslationPicker));
+            picker = None;
+        }
+
+        if trimmed == RECOMMENDATIONS {
+            artifacts.push((offset..line_end(text, offset, line), Artifact::Recommendations));
+        }
+    }
+    if let Some(start) = picker {
+        artifacts.push((start..text.len(), Artifact::TranslationPicker));
+    }
+
+    if let Some(&(offset, line)) = lines.last() {
+        if let Some(start) = embed_trailer_start(line) {
+            let start = if start == 0 {
+                // The whole line is the trailer: also remove the line break preceding it.
+                offset.saturating_sub(1)
+            } else {
+                offset + start
+            };
+            if !artifacts.iter().any(|(range, _)| range.contains(&start)) {
+                artifacts.push((start..text.len(), Artifact::EmbedTrailer));
+            }
+        }
+    }
+
+    // Artifacts may overlap, e.g. a contributors header ending with a translation picker.
+    artifacts.sort_by_key(|(range, _)| range.start);
+    let mut end = 0;
+    artifacts.retain(|(range, _)| {
+        let keep = range.start >= end;
+        if keep {
+            end = range.end;
+        }
+        keep
+    });
+    artifacts
+}
+
+/// Byte offset in `line` of a trailing `Embed` preceded by the embed count. A line made of `Embed` alone
+/// also matches, but lyrics merely ending with the word, as in `Deep in the Embed`, do not.
+fn embed_trailer_start(line: &str) -> Option<usize> {
+    let without_embed = line.trim_end().strip_suffix(EMBED)?;
+    let without_count = without_embed.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_count.trim().is_empty() {
+        Some(0)
+    } else if without_count.len() < without_embed.len() {
+        Some(without_count.trim_end().len())
+    } else {
+        None
+    }
+}
+
+/// `57 Contributors...`, with or without a space before `Contributors`.
+fn is_contributors_header(line: &str) -> bool {
+    let rest = line.trim_start().trim_start_matches(|c: char| c.is_ascii_digit());
+    rest.len() < line.trim_start().len() && rest.trim_start().starts_with(CONTRIBUTORS)
+}
+
+fn is_language(line: &str) -> bool {
+    LANGUAGES.contains(&line.trim())
+}
+
+fn lines_with_offsets(text: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+    text.split('\n')
+        .map(|line| {
+            let start = offset;
+            offset += line.len() + 1;
+            (start, line)
+        })
+        .collect()
+}
+
+/// End of `line`, including its line break if it has one.
+fn line_end(text: &str, offset: usize, line: &str) -> usize {
+    (offset + line.len() + 1).min(text.len())
+}
+
+fn char_to_byte(text: &str, chars: usize) -> usize {
+    text.char_indices().nth(chars).map_or(text.len(), |(byte, _)| byte)
+}
+
+/// Position of `byte` once `ranges` (sorted) have been removed.
+fn shift(byte: usize, ranges: &[Range<usize>]) -> usize {
+    let removed: usize = ranges
+        .iter()
+        .take_while(|range| range.start < byte)
+        .map(|range| range.end.min(byte) - range.start)
+        .sum();
+    byte - removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(text: &str) -> ScrapedLyrics {
+        Cleaner::new().clean(ScrapedLyrics {
+            text: String::from(text),
+            ..Default::default()
+        })
+    }
+
+    fn artifacts(lyrics: &ScrapedLyrics) -> Vec<Artifact> {
+        lyrics.cleaning.removals.iter().map(|removal| removal.artifact).collect()
+    }
+
+    #[test]
+    fn removes_embed_trailers() {
+        assert_eq!(clean("Last line\n123Embed").text, "Last line");
+        assert_eq!(clean("Last line\nEmbed").text, "Last line");
+        let lyrics = clean("Last line42Embed");
+        assert_eq!(lyrics.text, "Last line");
+        assert_eq!(artifacts(&lyrics), [Artifact::EmbedTrailer]);
+        assert_eq!(lyrics.cleaning.removals[0].text, "42Embed");
+    }
+
+    #[test]
+    fn keeps_lyrics_ending_with_the_word_embed() {
+        let lyrics = clean("We embed\nDeep in the Embed");
+        assert_eq!(lyrics.text, "We embed\nDeep in the Embed");
+        assert!(lyrics.cleaning.is_empty());
+    }
+
+    #[test]
+    fn removes_recommendations_on_their_own_line() {
+        let lyrics = clean("First line\nYou might also like\nSecond line");
+        assert_eq!(lyrics.text, "First line\nSecond line");
+        assert_eq!(artifacts(&lyrics), [Artifact::Recommendations]);
+    }
+
+    #[test]
+    fn keeps_lyrics_starting_with_the_recommendations_label() {
+        let lyrics = clean("You might also like me\nIf you knew");
+        assert_eq!(lyrics.text, "You might also like me\nIf you knew");
+        assert!(lyrics.cleaning.is_empty());
+    }
+
+    #[test]
+    fn removes_contributors_header() {
+        let lyrics = clean("57 ContributorsTranslationsEspañolWonderwall Lyrics\nToday is gonna be the day");
+        assert_eq!(lyrics.text, "Today is gonna be the day");
+        assert_eq!(artifacts(&lyrics), [Artifact::ContributorsHeader]);
+    }
+
+    #[test]
+    fn removes_translation_picker_below_the_contributors_header() {
+        let lyrics = clean("57 Contributors\nTranslations\nEspañol\nFrançais\nToday is gonna be the day");
+        assert_eq!(lyrics.text, "Today is gonna be the day");
+        assert_eq!(artifacts(&lyrics), [Artifact::ContributorsHeader, Artifact::TranslationPicker]);
+    }
+
+    #[test]
+    fn keeps_translations_mentioned_in_the_lyrics() {
+        let text = "Lost in the words\nTranslations\nEnglish\nIs all I speak";
+        let lyrics = clean(text);
+        assert_eq!(lyrics.text, text);
+        assert!(lyrics.cleaning.is_empty());
+    }
+}
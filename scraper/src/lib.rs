@@ -1 +1,8 @@
-pub mod scraper;
\ No newline at end of file
+pub mod album;
+pub mod cleaning;
+pub mod metadata;
+pub mod provider;
+pub mod render;
+pub mod robots;
+pub mod scraper;
+pub mod sections;
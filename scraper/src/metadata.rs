@@ -0,0 +1,415 @@
+use genius::model::{
+    artist::PrimaryArtist,
+    page::{PageAlbum, PageCredit, PageTag, SongPageMetadata, TranslationLink},
+    song::ArtistSong,
+};
+use once_cell::sync::Lazy;
+use scraper::{Html, Selector};
+use serde_json::{Map, Value};
+
+const PRELOADED_STATE: &str = "window.__PRELOADED_STATE__";
+const JSON_PARSE: &str = "JSON.parse(";
+const GENIUS_URL: &str = "https://genius.com";
+
+static SONG_PATH_SELECTOR: Lazy<Selector> = Lazy::new(|| {
+    Selector::parse(r#"meta[name="newrelic-resource-path"], meta[property="twitter:app:url:iphone"]"#).unwrap()
+});
+static CANONICAL_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(r#"link[rel="canonical"]"#).unwrap());
+static TITLE_SELECTOR: Lazy<Selector> = Lazy::new(|| Selector::parse(r#"meta[property="og:title"]"#).unwrap());
+static TRANSLATION_LINK_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"[class*="LyricsHeader__Translations"] a[href]"#).unwrap());
+
+/// A song page along with its `window.__PRELOADED_STATE__` blob, which is parsed once and read by
+/// [`SongPage::song`], [`SongPage::metadata`] and [`SongPage::translations`].
+pub struct SongPage<'a> {
+    html: &'a str,
+    state: Option<Value>,
+}
+
+impl<'a> SongPage<'a> {
+    pub fn parse(html: &'a str) -> Self {
+        Self {
+            html,
+            state: preloaded_state(html),
+        }
+    }
+
+    pub fn html(&self) -> &'a str {
+        self.html
+    }
+
+    /// Reads the song the page is about, as the API would return it.
+    ///
+    /// The song is read from the preloaded state. Fields missing from it, or from pages without one,
+    /// are left empty, except for the ID, URL and title found in their `<meta>` and `<link>` tags.
+    /// Returns `None` if the page has no song ID.
+    pub fn song(&self) -> Option<ArtistSong> {
+        self.state
+            .as_ref()
+            .and_then(song_from_state)
+            .or_else(|| song_from_tags(self.html))
+    }
+
+    /// Reads the preloaded state into [`SongPageMetadata`].
+    ///
+    /// Returns `None` if the page has no preloaded state or if it does not describe a song.
+    pub fn metadata(&self) -> Option<SongPageMetadata> {
+        metadata_from_state(self.state.as_ref()?)
+    }
+
+    /// Finds the community translations the page links to.
+    ///
+    /// They are read from the preloaded state, or from the translations menu of pages without one.
+    pub fn translations(&self) -> Vec<TranslationLink> {
+        let from_state = self.state.as_ref().and_then(|state| {
+            let song = entity(&state["entities"], "songs", &state["songPage"]["song"])?;
+            Some(translations_from_state(&state["entities"], song))
+        });
+        if let Some(translations) = from_state.filter(|translations| !translations.is_empty()) {
+            return translations;
+        }
+
+        let document = Html::parse_document(self.html);
+        document
+            .select(&TRANSLATION_LINK_SELECTOR)
+            .filter_map(|link| {
+                let language = link.text().collect::<String>().trim().to_string();
+                let url = link.value().attr("href")?;
+                (!language.is_empty()).then(|| TranslationLink {
+                    language,
+                    song_id: None,
+                    title: None,
+                    url: String::from(url),
+                })
+            })
+            .collect()
+    }
+}
+
+fn song_from_state(state: &Value) -> Option<ArtistSong> {
+    let entities = &state["entities"];
+    let song_id = &state["songPage"]["song"];
+    let song = entity(entities, "songs", song_id)?;
+
+    // Entities use camelCase keys and refer to artists by ID.
+    let mut fields: Map<String, Value> = song.as_object()?.iter().map(|(key, value)| (snake_case(key), value.clone())).collect();
+    let artist = song.get("primaryArtist").and_then(|id| entity(entities, "artists", id));
+    if let Some(artist) = artist {
+        fields.insert(
+            String::from("primary_artist"),
+            serde_json::json!({ "id": artist["id"], "name": artist["name"] }),
+        );
+    }
+    serde_json::from_value(Value::Object(fields)).ok().or_else(|| {
+        let mut partial = minimal_song(
+            id(song_id)?,
+            song["title"].as_str().unwrap_or_default(),
+            song["url"].as_str().unwrap_or_default(),
+        );
+        if let Some(artist) = artist {
+            partial.primary_artist = PrimaryArtist {
+                id: artist["id"].as_u64().unwrap_or_default() as u32,
+                name: artist["name"].as_str().unwrap_or_default().to_string(),
+            };
+            partial.artist_names = partial.primary_artist.name.clone();
+        }
+        partial.release_date_for_display = song["releaseDateForDisplay"].as_str().map(String::from);
+        Some(partial)
+    })
+}
+
+fn song_from_tags(html: &str) -> Option<ArtistSong> {
+    let document = Html::parse_document(html);
+    let id = document
+        .select(&SONG_PATH_SELECTOR)
+        .filter_map(|meta| meta.value().attr("content"))
+        .find_map(|path| path.rsplit_once("songs/")?.1.parse::<u32>().ok())?;
+    let url = document
+        .select(&CANONICAL_SELECTOR)
+        .find_map(|link| link.value().attr("href"))
+        .unwrap_or_default();
+    let title = document
+        .select(&TITLE_SELECTOR)
+        .find_map(|meta| meta.value().attr("content"))
+        .unwrap_or_default();
+    Some(minimal_song(id, title, url))
+}
+
+fn minimal_song(id: u32, title: &str, url: &str) -> ArtistSong {
+    ArtistSong {
+        annotation_count: None,
+        api_path: format!("/songs/{}", id),
+        artist_names: String::new(),
+        full_title: String::from(title),
+        header_image_thumbnail_url: String::new(),
+        header_image_url: String::new(),
+        id,
+        language: None,
+        lyrics_owner_id: None,
+        lyrics_state: String::new(),
+        path: String::from(url.strip_prefix(GENIUS_URL).unwrap_or_default()),
+        primary_artist: PrimaryArtist {
+            id: 0,
+            name: String::new(),
+        },
+        pyongs_count: None,
+        relationships_index_url: String::new(),
+        release_date_for_display: None,
+        song_art_image_thumbnail_url: String::new(),
+        song_art_image_url: String::new(),
+        title: String::from(title),
+        title_with_featured: String::from(title),
+        url: String::from(url),
+    }
+}
+
+fn snake_case(key: &str) -> String {
+    let mut snake = String::with_capacity(key.len() + 4);
+    for c in key.chars() {
+        if c.is_ascii_uppercase() {
+            snake.push('_');
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn metadata_from_state(state: &Value) -> Option<SongPageMetadata> {
+    let entities = &state["entities"];
+    let song_id = &state["songPage"]["song"];
+    let song = entity(entities, "songs", song_id)?;
+
+    let artist_names = |ids: &Value| -> Vec<String> {
+        ids.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|id| entity(entities, "artists", id)?["name"].as_str().map(String::from))
+            .collect()
+    };
+
+    let primary_tag = id(&song["primaryTag"]);
+    let mut tags: Vec<PageTag> = song["tags"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|tag_id| {
+            let tag = entity(entities, "tags", tag_id)?;
+            Some(PageTag {
+                id: id(&tag["id"])?,
+                name: tag["name"].as_str()?.to_string(),
+                primary: tag["primary"].as_bool().unwrap_or(false) || id(tag_id) == primary_tag,
+            })
+        })
+        .collect();
+    tags.sort_by_key(|tag| !tag.primary);
+
+    let album = entity(entities, "albums", &song["album"]).and_then(|album| {
+        Some(PageAlbum {
+            id: id(&album["id"])?,
+            name: album["name"].as_str()?.to_string(),
+            url: album["url"].as_str().map(String::from),
+        })
+    });
+
+    let credits = song["customPerformances"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|credit| {
+            Some(PageCredit {
+                label: credit["label"].as_str()?.to_string(),
+                artists: artist_names(&credit["artists"]),
+            })
+        })
+        .collect();
+
+    Some(SongPageMetadata {
+        song_id: id(&song["id"]).or_else(|| id(song_id)),
+        title: song["title"].as_str().map(String::from),
+        album,
+        tags,
+        release_date: song["releaseDateForDisplay"].as_str().map(String::from),
+        pageviews: song["stats"]["pageviews"].as_u64(),
+        writers: artist_names(&song["writerArtists"]),
+        producers: artist_names(&song["producerArtists"]),
+        credits,
+        translations: translations_from_state(entities, song),
+    })
+}
+
+fn translations_from_state(entities: &Value, song: &Value) -> Vec<TranslationLink> {
+    song["translationSongs"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|translation| {
+            // Translations are usually referred to by ID, but may be embedded.
+            let translation = match translation {
+                Value::Object(_) => translation,
+                _ => entity(entities, "songs", translation)?,
+            };
+            Some(TranslationLink {
+                language: translation["language"].as_str()?.to_string(),
+                song_id: id(&translation["id"]),
+                title: translation["title"].as_str().map(String::from),
+                url: translation["url"].as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Looks up an entity by the ID `id` refers to. IDs may be numbers or strings.
+fn entity<'a>(entities: &'a Value, kind: &str, id: &Value) -> Option<&'a Value> {
+    let id = match id {
+        Value::Number(id) => id.to_string(),
+        Value::String(id) => id.clone(),
+        _ => return None,
+    };
+    entities[kind].get(id).filter(|entity| entity.is_object())
+}
+
+/// Reads an ID that may be a number or a string.
+fn id(id: &Value) -> Option<u32> {
+    let id = id.as_u64().or_else(|| id.as_str()?.parse().ok())?;
+    u32::try_from(id).ok()
+}
+
+/// The state is either a `JSON.parse('...')` call on a JavaScript string literal, or an object literal.
+fn preloaded_state(html: &str) -> Option<Value> {
+    let start = html.find(PRELOADED_STATE)? + PRELOADED_STATE.len();
+    let assignment = html[start..].trim_start().strip_prefix('=')?.trim_start();
+
+    match assignment.strip_prefix(JSON_PARSE) {
+        Some(call) => {
+            let json = unescape_js_string(call.trim_start())?;
+            serde_json::from_str(&json).ok()
+        }
+        None => serde_json::Deserializer::from_str(assignment)
+            .into_iter::<Value>()
+            .next()?
+            .ok(),
+    }
+}
+
+/// Decodes the JavaScript string literal `literal` starts with.
+fn unescape_js_string(literal: &str) -> Option<String> {
+    let mut chars = literal.chars();
+    let quote = chars.next().filter(|quote| matches!(quote, '\'' | '"' | '`'))?;
+    let mut decoded = String::new();
+    let mut pending_surrogate: Option<u32> = None;
+
+    while let Some(c) = chars.next() {
+        let c = match c {
+            c if c == quote => return Some(decoded),
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'v' => '\u{b}',
+                '0' => '\0',
+                'x' => char::from_u32(hex(&mut chars, 2)?)?,
+                'u' => {
+                    let code = hex(&mut chars, 4)?;
+                    match (pending_surrogate.take(), code) {
+                        (None, 0xD800..=0xDBFF) => {
+                            pending_surrogate = Some(code);
+                            continue;
+                        }
+                        (Some(high), 0xDC00..=0xDFFF) => {
+                            char::from_u32(0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00))?
+                        }
+                        (_, code) => char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER),
+                    }
+                }
+                '\n' => continue,
+                escaped => escaped,
+            },
+            c => c,
+        };
+        decoded.push(c);
+    }
+    None
+}
+
+fn hex(chars: &mut std::str::Chars, digits: usize) -> Option<u32> {
+    let digits: String = chars.take(digits).collect();
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    fn page(state: &str) -> String {
+        format!("<html><script>window.__PRELOADED_STATE__ = {};</script></html>", state)
+    }
+
+    /// A song page listing its translations in its preloaded state, one listing them in its menu only,
+    /// and the pages of the translations.
+    fn translations_fixture(name: &str) -> String {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/translations").join(name);
+        fs::read_to_string(path).unwrap()
+    }
+
+    fn languages(links: &[TranslationLink]) -> Vec<(&str, Option<u32>)> {
+        links.iter().map(|link| (link.language.as_str(), link.song_id)).collect()
+    }
+
+    #[test]
+    fn reads_metadata_of_songs_referred_to_by_string_ids() {
+        let html = page(
+            r#"{"songPage":{"song":"378195"},"entities":{
+                "songs":{"378195":{"id":378195,"title":"Alright","album":"10","tags":["1"],"primaryTag":1,
+                    "writerArtists":[1421],"translationSongs":[]}},
+                "albums":{"10":{"id":10,"name":"To Pimp a Butterfly"}},
+                "tags":{"1":{"id":1,"name":"Rap"}},
+                "artists":{"1421":{"id":1421,"name":"Kendrick Lamar"}}}}"#,
+        );
+        let metadata = SongPage::parse(&html).metadata().unwrap();
+        assert_eq!(metadata.song_id, Some(378195));
+        assert_eq!(metadata.title.as_deref(), Some("Alright"));
+        assert_eq!(metadata.album.unwrap().name, "To Pimp a Butterfly");
+        assert_eq!(metadata.tags[0].name, "Rap");
+        assert!(metadata.tags[0].primary);
+        assert_eq!(metadata.writers, ["Kendrick Lamar"]);
+    }
+
+    #[test]
+    fn pages_without_a_song_have_no_metadata() {
+        assert!(SongPage::parse(&page(r#"{"songPage":{},"entities":{}}"#)).metadata().is_none());
+        assert!(SongPage::parse(&page(r#"{"songPage":{"song":1},"entities":{"songs":{}}}"#)).metadata().is_none());
+        assert!(SongPage::parse("<html></html>").metadata().is_none());
+    }
+
+    #[test]
+    fn reads_translations_referred_to_by_id_or_embedded() {
+        let html = translations_fixture("state.html");
+        let links = SongPage::parse(&html).translations();
+        // The menu is ignored when the state lists translations.
+        assert_eq!(languages(&links), [("es", Some(7512345)), ("fr", Some(7512346)), ("pt", Some(7512347))]);
+        assert_eq!(links[1].title.as_deref(), Some("Portishead - All Mine (Traduction Française)"));
+        assert!(links[1].url.starts_with("https://genius.com/Genius-traductions-francaises-"));
+    }
+
+    #[test]
+    fn reads_translations_from_the_menu_without_state() {
+        let html = translations_fixture("menu.html");
+        let links = SongPage::parse(&html).translations();
+        assert_eq!(languages(&links), [("Deutsch", None), ("Español", None)]);
+        assert!(links[0].url.starts_with("https://genius.com/Genius-deutsche-ubersetzungen-"));
+
+        // So are pages whose state lists no translation.
+        let html = format!(
+            "{}{}",
+            page(r#"{"songPage":{"song":1},"entities":{"songs":{"1":{"id":1,"translationSongs":[]}}}}"#),
+            html
+        );
+        assert_eq!(languages(&SongPage::parse(&html).translations()), [("Deutsch", None), ("Español", None)]);
+    }
+}
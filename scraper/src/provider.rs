@@ -0,0 +1,303 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use files::{file_manager::song_file_path, lrc::Lrc};
+use genius::model::song::ArtistSong;
+use log::debug;
+
+use crate::{
+    render::ScrapedLyrics,
+    scraper::{AppScraper, LyricsOutcome, ScrapeError, ScrapedPage},
+};
+
+/// Extensions of the files [`LocalDirectoryProvider`] looks for, in order of preference.
+const LYRICS_EXTENSIONS: [&str; 2] = ["txt", "lrc"];
+
+/// A source of lyrics for the songs returned by the Genius API.
+#[async_trait]
+pub trait LyricsProvider: Send + Sync {
+    async fn fetch(&self, song: &ArtistSong) -> Result<LyricsOutcome, ScrapeError>;
+
+    /// The lyrics along with whatever else the source knows about the song.
+    async fn fetch_page(&self, song: &ArtistSong) -> Result<ScrapedPage, ScrapeError> {
+        self.fetch(song).await.map(|outcome| ScrapedPage {
+            outcome,
+            song: None,
+            metadata: None,
+            translations: BTreeMap::new(),
+        })
+    }
+}
+
+/// Scrapes the song's Genius page.
+#[async_trait]
+impl LyricsProvider for AppScraper {
+    async fn fetch(&self, song: &ArtistSong) -> Result<LyricsOutcome, ScrapeError> {
+        self.from_url(&song.url).await
+    }
+
+    async fn fetch_page(&self, song: &ArtistSong) -> Result<ScrapedPage, ScrapeError> {
+        self.page(&song.url).await
+    }
+}
+
+/// Reads lyrics from a directory laid out as `<artist>/<title>.txt` or `<artist>/<title>.lrc`,
+/// named after the song's primary artist and title. The timings of `.lrc` files are kept in [`ScrapedLyrics::synced`].
+pub struct LocalDirectoryProvider {
+    dir: PathBuf,
+}
+
+impl LocalDirectoryProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The first lyrics file that exists for `song`.
+    fn find(&self, song: &ArtistSong) -> Option<PathBuf> {
+        LYRICS_EXTENSIONS
+            .iter()
+            .map(|extension| song_file_path(&self.dir, song, extension))
+            .find(|path| path.is_file())
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for LocalDirectoryProvider {
+    async fn fetch(&self, song: &ArtistSong) -> Result<LyricsOutcome, ScrapeError> {
+        let Some(path) = self.find(song) else {
+            return Ok(LyricsOutcome::NotFound);
+        };
+        debug!("Reading lyrics of song `{}` from {:?}", song.id, path);
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        let lyrics = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("lrc") => {
+                let lrc = Lrc::parse(&content);
+                ScrapedLyrics {
+                    text: lrc.text(),
+                    synced: Some(lrc.synced()),
+                    ..Default::default()
+                }
+            }
+            _ => ScrapedLyrics {
+                text: content.trim().to_string(),
+                ..Default::default()
+            },
+        };
+        Ok(LyricsOutcome::Lyrics(lyrics))
+    }
+}
+
+/// Tries providers in turn, moving on to the next one when a provider fails or does not have the song.
+///
+/// The result of the last provider is returned if none of them has the song.
+pub struct FallbackProvider {
+    providers: Vec<Box<dyn LyricsProvider>>,
+}
+
+impl FallbackProvider {
+    pub fn new(provider: impl LyricsProvider + 'static) -> Self {
+        Self {
+            providers: vec![Box::new(provider)],
+        }
+    }
+
+    /// Tries `provider` after the previous ones.
+    pub fn or(mut self, provider: impl LyricsProvider + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+#[async_trait]
+impl LyricsProvider for FallbackProvider {
+    async fn fetch(&self, song: &ArtistSong) -> Result<LyricsOutcome, ScrapeError> {
+        self.fetch_page(song).await.map(|page| page.outcome)
+    }
+
+    async fn fetch_page(&self, song: &ArtistSong) -> Result<ScrapedPage, ScrapeError> {
+        let mut result = Ok(ScrapedPage {
+            outcome: LyricsOutcome::NotFound,
+            song: None,
+            metadata: None,
+            translations: BTreeMap::new(),
+        });
+        for provider in &self.providers {
+            result = provider.fetch_page(song).await;
+            match &result {
+                Ok(ScrapedPage {
+                    outcome: LyricsOutcome::NotFound | LyricsOutcome::NoContainer,
+                    ..
+                }) => continue,
+                Ok(_) => break,
+                Err(err) => debug!("Falling back for song `{}`: {}", song.id, err),
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs, io,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use super::*;
+
+    fn song(artist: &str, title: &str) -> ArtistSong {
+        serde_json::from_value(serde_json::json!({
+            "api_path": "/songs/1",
+            "artist_names": artist,
+            "full_title": format!("{} by {}", title, artist),
+            "header_image_thumbnail_url": "",
+            "header_image_url": "",
+            "id": 1,
+            "lyrics_state": "complete",
+            "path": "/song-lyrics",
+            "primary_artist": { "id": 1, "name": artist },
+            "relationships_index_url": "",
+            "song_art_image_thumbnail_url": "",
+            "song_art_image_url": "",
+            "title_with_featured": title,
+            "title": title,
+            "url": "https://genius.com/song-lyrics",
+        }))
+        .unwrap()
+    }
+
+    fn write(dir: &Path, path: &str, content: &str) {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_the_file_of_the_artist_and_title() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Portishead/All Mine.txt", "\nYou won't fall for anything\n");
+        write(dir.path(), "Portishead/Roads.txt", "Oh, can't anybody see");
+        let provider = LocalDirectoryProvider::new(dir.path());
+
+        let lyrics = provider.fetch(&song("Portishead", "All Mine")).await.unwrap().into_lyrics();
+        assert_eq!(lyrics.text, "You won't fall for anything");
+        assert!(lyrics.synced.is_none());
+
+        // Names are sanitized the way lyrics files are written.
+        write(dir.path(), "AC_DC/Back In Black.txt", "Back in black");
+        let lyrics = provider.fetch(&song("AC/DC", "Back In Black")).await.unwrap().into_lyrics();
+        assert_eq!(lyrics.text, "Back in black");
+    }
+
+    #[tokio::test]
+    async fn prefers_text_files_over_lrc_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Portishead/All Mine.lrc", "[00:01.00]From the LRC file");
+        let provider = LocalDirectoryProvider::new(dir.path());
+
+        let lyrics = provider.fetch(&song("Portishead", "All Mine")).await.unwrap().into_lyrics();
+        assert_eq!(lyrics.text, "From the LRC file");
+        assert_eq!(lyrics.synced.map(|synced| synced.len()), Some(1));
+
+        write(dir.path(), "Portishead/All Mine.txt", "From the text file");
+        let lyrics = provider.fetch(&song("Portishead", "All Mine")).await.unwrap().into_lyrics();
+        assert_eq!(lyrics.text, "From the text file");
+        assert!(lyrics.synced.is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_files_are_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "Portishead/Roads.txt", "Oh, can't anybody see");
+        let provider = LocalDirectoryProvider::new(dir.path());
+
+        for song in [song("Portishead", "All Mine"), song("Massive Attack", "Roads")] {
+            let outcome = provider.fetch(&song).await.unwrap();
+            assert!(matches!(outcome, LyricsOutcome::NotFound), "{:?}", outcome);
+        }
+    }
+
+    /// Answers every song the same way and counts how often it was asked.
+    struct Stub {
+        answer: fn() -> Result<LyricsOutcome, ScrapeError>,
+        calls: AtomicUsize,
+    }
+
+    impl Stub {
+        fn new(answer: fn() -> Result<LyricsOutcome, ScrapeError>) -> Arc<Self> {
+            Arc::new(Self {
+                answer,
+                calls: AtomicUsize::new(0),
+            })
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl LyricsProvider for Arc<Stub> {
+        async fn fetch(&self, _song: &ArtistSong) -> Result<LyricsOutcome, ScrapeError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            (self.answer)()
+        }
+    }
+
+    fn lyrics(text: &str) -> LyricsOutcome {
+        LyricsOutcome::Lyrics(ScrapedLyrics {
+            text: String::from(text),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn falls_back_until_a_provider_has_the_song() {
+        let not_found = Stub::new(|| Ok(LyricsOutcome::NotFound));
+        let no_container = Stub::new(|| Ok(LyricsOutcome::NoContainer));
+        let failing = Stub::new(|| Err(ScrapeError::Io(io::Error::other("unreadable"))));
+        let found = Stub::new(|| Ok(lyrics("Found")));
+        let unused = Stub::new(|| Ok(lyrics("Unused")));
+        let provider = FallbackProvider::new(Arc::clone(&not_found))
+            .or(Arc::clone(&no_container))
+            .or(Arc::clone(&failing))
+            .or(Arc::clone(&found))
+            .or(Arc::clone(&unused));
+
+        let outcome = provider.fetch(&song("Portishead", "All Mine")).await.unwrap();
+        assert_eq!(outcome.into_lyrics().text, "Found");
+        let calls: Vec<_> = [not_found, no_container, failing, found, unused].iter().map(|stub| stub.calls()).collect();
+        assert_eq!(calls, [1, 1, 1, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn stops_at_songs_without_lyrics() {
+        let instrumental = Stub::new(|| Ok(LyricsOutcome::Instrumental));
+        let unused = Stub::new(|| Ok(lyrics("Unused")));
+        let provider = FallbackProvider::new(Arc::clone(&instrumental)).or(Arc::clone(&unused));
+
+        let outcome = provider.fetch(&song("Boards of Canada", "Roygbiv")).await.unwrap();
+        assert!(matches!(outcome, LyricsOutcome::Instrumental), "{:?}", outcome);
+        assert_eq!(unused.calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn returns_the_last_result_when_no_provider_has_the_song() {
+        let provider = FallbackProvider::new(Stub::new(|| Ok(LyricsOutcome::NotFound)))
+            .or(Stub::new(|| Err(ScrapeError::Io(io::Error::other("unreadable")))));
+
+        let result = provider.fetch(&song("Portishead", "All Mine")).await;
+        assert!(matches!(result, Err(ScrapeError::Io(_))), "{:?}", result);
+    }
+}
@@ -0,0 +1,237 @@
+use genius::model::lyrics::{AnnotatedSpan, SyncedLine};
+use scraper::{node::Node, ElementRef};
+
+use crate::cleaning::CleanReport;
+
+/// Elements that start a new line. Everything else is rendered inline.
+const BLOCK_ELEMENTS: [&str; 15] = [
+    "address", "article", "blockquote", "div", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "li", "p",
+    "section",
+];
+
+/// Elements whose content is never part of the lyrics.
+const SKIPPED_ELEMENTS: [&str; 5] = ["button", "noscript", "script", "style", "template"];
+
+const GENIUS_URL: &str = "https://genius.com";
+
+/// Lyrics scraped from a song page.
+#[derive(Debug, Clone, Default)]
+pub struct ScrapedLyrics {
+    pub text: String,
+    /// Annotated fragments of `text`, in order of appearance.
+    pub annotations: Vec<AnnotatedSpan>,
+    /// Boilerplate removed by the [`Cleaner`](crate::cleaning::Cleaner).
+    pub cleaning: CleanReport,
+    /// Time-tagged lines, which song pages do not have but LRC files do.
+    pub synced: Option<Vec<SyncedLine>>,
+}
+
+/// Renders `data-lyrics-container` elements as plain text, one container after another.
+///
+/// Line breaks come from `<br>` and from the boundaries of block elements only, so annotation
+/// links and inline markup such as `<i>` or `<b>` stay on the line they belong to. Elements
+/// Genius marks with `data-exclude-from-selection` (headers and ads injected in the lyrics) are skipped.
+/// Links to referents, e.g. `<a href="/12345678/...">`, are kept as annotated spans.
+pub fn render_lyrics<'a>(containers: impl IntoIterator<Item = ElementRef<'a>>) -> ScrapedLyrics {
+    let mut renderer = Renderer::default();
+    for container in containers {
+        renderer.children(container);
+        renderer.end_block();
+    }
+    renderer.finish()
+}
+
+/// Builds the text one line at a time, trimming lines as they are completed so that
+/// span offsets recorded along the way stay valid.
+#[derive(Default)]
+struct Renderer {
+    text: String,
+    spans: Vec<(usize, usize, u32)>,
+}
+
+impl Renderer {
+    fn children(&mut self, element: ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.push(text),
+                Node::Element(_) => {
+                    if let Some(child) = ElementRef::wrap(child) {
+                        self.element(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, element: ElementRef) {
+        let value = element.value();
+        let name = value.name();
+        if SKIPPED_ELEMENTS.contains(&name) || value.attr("data-exclude-from-selection") == Some("true") {
+            return;
+        }
+        if name == "br" {
+            self.break_line();
+            return;
+        }
+
+        let block = BLOCK_ELEMENTS.contains(&name);
+        if block {
+            self.end_block();
+        }
+        let referent_id = match name {
+            "a" => value.attr("href").and_then(referent_id),
+            _ => None,
+        };
+        let start = self.text.len();
+        self.children(element);
+        if let Some(referent_id) = referent_id {
+            self.spans.push((start, self.text.len(), referent_id));
+        }
+        if block {
+            self.end_block();
+        }
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.text.is_empty() || self.text.ends_with('\n')
+    }
+
+    fn push(&mut self, text: &str) {
+        let text = if self.at_line_start() { text.trim_start() } else { text };
+        self.text.push_str(text);
+    }
+
+    /// Ends the current line. Blank lines before the first line of text are dropped.
+    fn break_line(&mut self) {
+        let len = self.text.trim_end_matches(|c: char| c != '\n' && c.is_whitespace()).len();
+        self.text.truncate(len);
+        for (start, end, _) in self.spans.iter_mut() {
+            *start = (*start).min(len);
+            *end = (*end).min(len);
+        }
+        if !self.text.is_empty() {
+            self.text.push('\n');
+        }
+    }
+
+    /// Ends the current line unless it is empty, so nested blocks do not produce blank lines.
+    fn end_block(&mut self) {
+        if !self.at_line_start() {
+            self.break_line();
+        }
+    }
+
+    fn finish(mut self) -> ScrapedLyrics {
+        let len = self.text.trim_end().len();
+        self.text.truncate(len);
+        self.spans.sort_by_key(|(start, _, _)| *start);
+
+        let annotations = self
+            .spans
+            .into_iter()
+            .filter_map(|(start, end, referent_id)| {
+                let (start, end) = (start.min(len), end.min(len));
+                let fragment = &self.text[start..end];
+                let start = start + (fragment.len() - fragment.trim_start().len());
+                let end = end - (fragment.len() - fragment.trim_end().len());
+                if start >= end {
+                    return None;
+                }
+                Some(AnnotatedSpan {
+                    start: self.text[..start].chars().count(),
+                    end: self.text[..end].chars().count(),
+                    fragment: self.text[start..end].to_string(),
+                    referent_id,
+                })
+            })
+            .collect();
+
+        ScrapedLyrics {
+            text: self.text,
+            annotations,
+            cleaning: CleanReport::default(),
+            synced: None,
+        }
+    }
+}
+
+/// Extracts the referent ID from links such as `/12345678/Artist-song-lyrics/fragment`.
+fn referent_id(href: &str) -> Option<u32> {
+    let path = href.strip_prefix(GENIUS_URL).unwrap_or(href);
+    path.strip_prefix('/')?.split('/').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, path::PathBuf};
+
+    use scraper::{Html, Selector};
+
+    use super::*;
+
+    /// Saved song pages, each with a `.snap` file holding the expected text and annotated spans.
+    /// Set `UPDATE_SNAPSHOTS=1` to rewrite the snapshots.
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/render")
+    }
+
+    fn render_page(html: &str) -> ScrapedLyrics {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(r#"div[data-lyrics-container="true"]"#).unwrap();
+        render_lyrics(document.select(&selector))
+    }
+
+    fn snapshot(lyrics: &ScrapedLyrics) -> String {
+        let mut snapshot = format!("{}\n---\n", lyrics.text);
+        for span in &lyrics.annotations {
+            snapshot.push_str(&format!("{}..{} {} {:?}\n", span.start, span.end, span.referent_id, span.fragment));
+        }
+        snapshot
+    }
+
+    #[test]
+    fn renders_saved_pages_as_in_snapshots() {
+        let mut pages: Vec<_> = fs::read_dir(fixtures_dir())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|extension| extension == "html"))
+            .collect();
+        pages.sort();
+        assert!(!pages.is_empty());
+
+        for page in pages {
+            let actual = snapshot(&render_page(&fs::read_to_string(&page).unwrap()));
+            let snapshot_path = page.with_extension("snap");
+            if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+                fs::write(&snapshot_path, &actual).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&snapshot_path).unwrap();
+            assert_eq!(actual, expected, "snapshot of {:?}", page);
+        }
+    }
+
+    #[test]
+    fn annotation_offsets_are_characters_into_the_text() {
+        let html = fs::read_to_string(fixtures_dir().join("non_ascii.html")).unwrap();
+        let lyrics = render_page(&html);
+
+        assert_eq!(lyrics.annotations.len(), 2);
+        for span in &lyrics.annotations {
+            let fragment: String = lyrics.text.chars().skip(span.start).take(span.end - span.start).collect();
+            assert_eq!(fragment, span.fragment);
+        }
+        assert_eq!(lyrics.annotations[1].fragment, "naïve ☂ façade");
+        assert_eq!(lyrics.annotations[1].referent_id, 31338);
+    }
+
+    #[test]
+    fn keeps_inline_markup_on_its_line() {
+        let html = r#"<div data-lyrics-container="true">A <i>b</i> <b>c</b> <a href="/1/x">d</a><br>e</div>"#;
+        let lyrics = render_page(html);
+        assert_eq!(lyrics.text, "A b c d\ne");
+        assert_eq!(lyrics.annotations[0].start, 6);
+        assert_eq!(lyrics.annotations[0].end, 7);
+    }
+}
@@ -0,0 +1,199 @@
+/// The rules of a `robots.txt` file that apply to one user agent.
+///
+/// Rules are matched by prefix, with support for `*` wildcards and `$` anchors. The longest
+/// matching rule wins, and `Allow` wins over `Disallow` when both are as long.
+#[derive(Debug, Clone, Default)]
+pub struct Robots {
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+impl Robots {
+    /// Allows every path, used when a site has no `robots.txt`.
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Parses `content`, keeping the group that names the product token of `user_agent`, or the `*` group
+    /// if none does. Tokens are compared case-insensitively.
+    pub fn parse(content: &str, user_agent: &str) -> Self {
+        let user_agent = user_agent_token(user_agent);
+        let mut specific: Option<Vec<Rule>> = None;
+        let mut wildcard: Option<Vec<Rule>> = None;
+
+        // Consecutive `User-agent` lines share the rules that follow them.
+        let mut agents: Vec<String> = vec![];
+        let mut rules: Vec<Rule> = vec![];
+        let mut in_rules = false;
+        let mut end_group = |agents: &mut Vec<String>, rules: &mut Vec<Rule>| {
+            for agent in agents.drain(..) {
+                if agent == "*" {
+                    wildcard.get_or_insert_with(Vec::new).extend(rules.iter().cloned());
+                } else if !user_agent.is_empty() && user_agent_token(&agent) == user_agent {
+                    specific.get_or_insert_with(Vec::new).extend(rules.iter().cloned());
+                }
+            }
+            rules.clear();
+        };
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match field.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        end_group(&mut agents, &mut rules);
+                        in_rules = false;
+                    }
+                    agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty `Disallow` allows everything, which is the default.
+                    if !value.is_empty() {
+                        rules.push(Rule {
+                            allow: field.trim().eq_ignore_ascii_case("allow"),
+                            pattern: String::from(value),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        end_group(&mut agents, &mut rules);
+
+        Self {
+            rules: specific.or(wildcard).unwrap_or_default(),
+        }
+    }
+
+    /// Whether `path` (including its query string) may be fetched.
+    pub fn is_allowed(&self, path: &str) -> bool {
+        let rule = self
+            .rules
+            .iter()
+            .filter(|rule| matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow));
+        match rule {
+            Some(rule) => rule.allow,
+            None => true,
+        }
+    }
+}
+
+/// The product token of a user agent, e.g. `lyri` for `lyri/0.1 (+https://example.com)`.
+fn user_agent_token(user_agent: &str) -> String {
+    user_agent
+        .split(['/', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(prefix) = parts.next() else {
+        return true;
+    };
+    let Some(mut rest) = path.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        let last = i == parts.len() - 1;
+        if last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Comments and unknown fields are ignored.
+Sitemap: https://example.com/sitemap.xml
+
+User-agent: *
+Disallow: /search
+Allow: /search/about
+
+User-agent: Lyri
+User-agent: other-bot
+Disallow: /private/
+Allow: /private/public
+Disallow: /*.json$
+Disallow: /tmp*/cache
+
+User-agent: lyri-preview
+Disallow: /
+";
+
+    #[test]
+    fn picks_the_group_of_the_product_token() {
+        let robots = Robots::parse(ROBOTS, "lyri/0.1 (+https://example.com)");
+        assert!(!robots.is_allowed("/private/songs"));
+        // The `*` group does not apply once a group names the agent.
+        assert!(robots.is_allowed("/search?q=portishead"));
+
+        let robots = Robots::parse(ROBOTS, "LYRI");
+        assert!(!robots.is_allowed("/private/songs"));
+    }
+
+    #[test]
+    fn falls_back_to_the_wildcard_group() {
+        // Neither a prefix nor an extension of a listed token matches it.
+        for user_agent in ["lyr/1.0", "lyrics/1.0", "Mozilla/5.0 (compatible; lyri)", ""] {
+            let robots = Robots::parse(ROBOTS, user_agent);
+            assert!(!robots.is_allowed("/search?q=portishead"), "{}", user_agent);
+            assert!(robots.is_allowed("/search/about"), "{}", user_agent);
+            assert!(robots.is_allowed("/private/songs"), "{}", user_agent);
+        }
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        let robots = Robots::parse(ROBOTS, "lyri");
+        assert!(robots.is_allowed("/private/public/song"));
+        assert!(!robots.is_allowed("/private/publi"));
+        assert!(robots.is_allowed("/songs"));
+
+        let tie = Robots::parse("User-agent: *\nDisallow: /page\nAllow: /page", "lyri");
+        assert!(tie.is_allowed("/page"));
+    }
+
+    #[test]
+    fn matches_wildcards_and_anchors() {
+        let robots = Robots::parse(ROBOTS, "lyri");
+        assert!(!robots.is_allowed("/songs/1.json"));
+        assert!(robots.is_allowed("/songs/1.json?page=2"));
+        assert!(!robots.is_allowed("/tmp-1/cache/page"));
+        assert!(robots.is_allowed("/tmp-1/page"));
+    }
+
+    #[test]
+    fn allows_everything_without_rules() {
+        assert!(Robots::allow_all().is_allowed("/anything"));
+        assert!(Robots::parse("User-agent: *\nDisallow:", "lyri").is_allowed("/anything"));
+        assert!(Robots::parse("", "lyri").is_allowed("/anything"));
+    }
+}
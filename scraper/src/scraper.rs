@@ -1,43 +1,524 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    album::parse_album,
+    cleaning::Cleaner,
+    metadata::SongPage,
+    render::{render_lyrics, ScrapedLyrics},
+    robots::Robots,
+};
+
 use {
+    futures::future,
+    genius::model::{
+        lyrics::LyricsStatus,
+        page::{AlbumTracklist, SongPageMetadata},
+        song::ArtistSong,
+    },
+    log::{debug, warn},
+    net::{
+        client::{HttpClient, HttpRequest, HttpResponse, NetError},
+        rate_limit::RateLimiter,
+    },
     once_cell::sync::Lazy,
-    reqwest::{Client, Error},
+    reqwest::{header::USER_AGENT, Client, Url},
     scraper::{Html, Selector},
+    tokio::sync::{OnceCell, Semaphore},
 };
 
+const DEFAULT_MAX_CONCURRENCY: usize = 50;
+const ROBOTS_PATH: &str = "/robots.txt";
+
 static LYRIC_SELECTOR: Lazy<Selector> =
     Lazy::new(|| Selector::parse(r#"div[data-lyrics-container="true"]"#).unwrap());
+static PLACEHOLDER_SELECTOR: Lazy<Selector> =
+    Lazy::new(|| Selector::parse(r#"div[class*="LyricsPlaceholder"]"#).unwrap());
+
+/// What a song page contains instead of, or in addition to, lyrics.
+#[derive(Debug, Clone)]
+pub enum LyricsOutcome {
+    Lyrics(ScrapedLyrics),
+    /// The page says the song is an instrumental.
+    Instrumental,
+    /// The page says the lyrics have yet to be released.
+    Unreleased,
+    /// The page has no lyrics container and no placeholder, e.g. because its layout changed.
+    NoContainer,
+    /// The source has no lyrics for the song, e.g. a lyrics directory without a file for it.
+    NotFound,
+}
+
+impl LyricsOutcome {
+    pub fn status(&self) -> LyricsStatus {
+        match self {
+            LyricsOutcome::Lyrics(_) => LyricsStatus::Available,
+            LyricsOutcome::Instrumental => LyricsStatus::Instrumental,
+            LyricsOutcome::Unreleased => LyricsStatus::Unreleased,
+            LyricsOutcome::NoContainer => LyricsStatus::NoContainer,
+            LyricsOutcome::NotFound => LyricsStatus::NotFound,
+        }
+    }
+
+    /// The scraped lyrics, or empty lyrics if there are none.
+    pub fn into_lyrics(self) -> ScrapedLyrics {
+        match self {
+            LyricsOutcome::Lyrics(lyrics) => lyrics,
+            _ => ScrapedLyrics::default(),
+        }
+    }
+}
+
+/// Everything scraped from a song page.
+#[derive(Debug, Clone)]
+pub struct ScrapedPage {
+    pub outcome: LyricsOutcome,
+    /// The song as described by the page itself, only read when enabled with [`AppScraper::song`].
+    /// See [`SongPage::song`].
+    pub song: Option<ArtistSong>,
+    /// Only parsed when enabled with [`AppScraper::metadata`].
+    pub metadata: Option<SongPageMetadata>,
+    /// Lyrics of the translations linked from the page by language, only fetched when enabled
+    /// with [`AppScraper::translations`].
+    pub translations: BTreeMap<String, ScrapedLyrics>,
+}
 
+/// Scrapes song pages. A single scraper is meant to be shared by every task, so that its
+/// concurrency limit, rate limiter and `robots.txt` rules apply to all of them.
 pub struct AppScraper {
-    client: Client,
+    client: HttpClient,
+    cleaner: Cleaner,
+    song: bool,
+    metadata: bool,
+    translations: bool,
+    user_agent: Option<String>,
+    timeout: Option<Duration>,
+    permits: Semaphore,
+    robots: Option<Mutex<HashMap<String, Arc<OnceCell<Robots>>>>>,
+}
+
+impl Default for AppScraper {
+    fn default() -> Self {
+        Self::with_client(HttpClient::new(Client::new()))
+    }
 }
 
 impl AppScraper {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a shared client, e.g. one backed by the on-disk cache.
+    pub fn with_client(client: HttpClient) -> Self {
         Self {
-            client: Client::new(),
+            client,
+            cleaner: Cleaner::new(),
+            song: false,
+            metadata: false,
+            translations: false,
+            user_agent: None,
+            timeout: None,
+            permits: Semaphore::new(DEFAULT_MAX_CONCURRENCY),
+            robots: None,
+        }
+    }
+
+    /// Replaces the default cleaner, e.g. to keep the page boilerplate.
+    pub fn cleaner(mut self, cleaner: Cleaner) -> Self {
+        self.cleaner = cleaner;
+        self
+    }
+
+    /// Also reads the song a page is about from the page itself, for pages the API was not asked about.
+    pub fn song(mut self, song: bool) -> Self {
+        self.song = song;
+        self
+    }
+
+    /// Also parses the metadata embedded in song pages, see [`AppScraper::page`].
+    pub fn metadata(mut self, metadata: bool) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Also fetches the community translations linked from song pages, see [`AppScraper::page`].
+    pub fn translations(mut self, translations: bool) -> Self {
+        self.translations = translations;
+        self
+    }
+
+    /// `User-Agent` header of every request, also used to pick the `robots.txt` rules that apply.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Spaces out requests that are not served from the cache, e.g. with a per-host [`RateLimiter`].
+    pub fn rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.client = self.client.rate_limiter(rate_limiter);
+        self
+    }
+
+    /// Maximum number of pages scraped at once. Defaults to 50.
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.permits = Semaphore::new(max_concurrency.max(1));
+        self
+    }
+
+    /// Skips pages the site's `robots.txt` disallows, failing with [`ScrapeError::Disallowed`].
+    pub fn respect_robots(mut self, respect_robots: bool) -> Self {
+        self.robots = respect_robots.then(|| Mutex::new(HashMap::new()));
+        self
+    }
+
+    pub async fn from_url(&self, url: &str) -> Result<LyricsOutcome, ScrapeError> {
+        self.page(url).await.map(|page| page.outcome)
+    }
+
+    /// Scrapes the lyrics of a song page and, if enabled, its metadata from the same response
+    /// and the lyrics of its translations.
+    pub async fn page(&self, url: &str) -> Result<ScrapedPage, ScrapeError> {
+        let html = self.html(url).await?;
+        let song_page = SongPage::parse(&html);
+        let mut page = self.scrape_song_page(&song_page);
+        if self.translations {
+            page.translations = self.fetch_translations(&song_page).await;
         }
+        Ok(page)
+    }
+
+    /// Scrapes a song page that was already downloaded, e.g. one saved from a browser.
+    pub fn scrape_html(&self, html: &str) -> ScrapedPage {
+        self.scrape_song_page(&SongPage::parse(html))
     }
 
-    pub async fn from_url(&self, url: &str) -> Result<String, Error> {
-        let response = self.client.get(url).send().await?.error_for_status();
+    /// Like [`AppScraper::scrape_html`], for a page whose preloaded state has already been parsed.
+    pub fn scrape_song_page(&self, page: &SongPage) -> ScrapedPage {
+        ScrapedPage {
+            outcome: self.scrape_lyrics(page.html()),
+            song: self.song.then(|| page.song()).flatten(),
+            metadata: self.metadata.then(|| page.metadata()).flatten(),
+            translations: BTreeMap::new(),
+        }
+    }
+
+    /// Scrapes the translations linked from a song page. Translations without lyrics are left out.
+    async fn fetch_translations(&self, page: &SongPage<'_>) -> BTreeMap<String, ScrapedLyrics> {
+        let links = page.translations();
+        let pages = future::join_all(links.iter().map(|link| self.html(&link.url))).await;
+        links
+            .into_iter()
+            .zip(pages)
+            .filter_map(|(link, page)| match page.map(|html| self.scrape_lyrics(&html)) {
+                Ok(LyricsOutcome::Lyrics(lyrics)) => Some((link.language, lyrics)),
+                Ok(outcome) => {
+                    debug!("No lyrics in the `{}` translation at `{}`: {:?}", link.language, link.url, outcome.status());
+                    None
+                }
+                Err(err) => {
+                    warn!("Could not scrape the `{}` translation at `{}`: {}", link.language, link.url, err);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Reads the tracklist of an album page.
+    pub async fn album(&self, url: &str) -> Result<AlbumTracklist, ScrapeError> {
+        let html = self.html(url).await?;
+        parse_album(&html, url).ok_or_else(|| ScrapeError::NoTracklist { url: String::from(url) })
+    }
+
+    /// Fetches a page, holding a permit and checking `robots.txt` first.
+    ///
+    /// The permits are what bound the number of pages fetched at once, so callers may start a task for
+    /// every page of an artist or album without limiting them themselves.
+    async fn html(&self, url: &str) -> Result<String, ScrapeError> {
+        let _permit = self.permits.acquire().await.expect("the semaphore is never closed");
+        if !self.is_allowed(url).await {
+            return Err(ScrapeError::Disallowed { url: String::from(url) });
+        }
+
+        let response = self.get(url).await?;
+        if response.is_success() {
+            Ok(response.body)
+        } else {
+            Err(ScrapeError::Status {
+                url: String::from(url),
+                status: response.status,
+            })
+        }
+    }
+
+    async fn get(&self, url: &str) -> Result<HttpResponse, NetError> {
+        let mut request = HttpRequest::get(url);
+        if let Some(user_agent) = &self.user_agent {
+            request = request.header(USER_AGENT.as_str(), user_agent);
+        }
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        self.client.send(request).await
+    }
 
-        match response {
-            Ok(res) => {
-                let lyrics = self.scrape_lyrics(&res.text().await?);
-                Ok(lyrics)
+    /// Fetches the `robots.txt` of each host once, when its first page is scraped.
+    async fn is_allowed(&self, url: &str) -> bool {
+        let (Some(robots), Ok(url)) = (&self.robots, Url::parse(url)) else {
+            return true;
+        };
+        let origin = url.origin().ascii_serialization();
+        let cell = {
+            let mut robots = robots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            Arc::clone(robots.entry(origin.clone()).or_default())
+        };
+        let robots = cell.get_or_init(|| self.fetch_robots(origin)).await;
+
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => String::from(url.path()),
+        };
+        robots.is_allowed(&path)
+    }
+
+    async fn fetch_robots(&self, origin: String) -> Robots {
+        let url = format!("{}{}", origin, ROBOTS_PATH);
+        match self.get(&url).await {
+            Ok(response) if response.is_success() => {
+                Robots::parse(&response.body, self.user_agent.as_deref().unwrap_or_default())
+            }
+            Ok(_) => Robots::allow_all(),
+            Err(err) => {
+                warn!("Could not fetch `{}`, assuming every page is allowed: {}", url, err);
+                Robots::allow_all()
             }
-            Err(err) => Err(err),
         }
     }
 
-    fn scrape_lyrics(&self, html: &str) -> String {
+    fn scrape_lyrics(&self, html: &str) -> LyricsOutcome {
         let document = Html::parse_document(html);
-        let lyrics_containers = document.select(&LYRIC_SELECTOR);
+        let mut containers = document.select(&LYRIC_SELECTOR).peekable();
+        if containers.peek().is_some() {
+            return LyricsOutcome::Lyrics(self.cleaner.clean(render_lyrics(containers)));
+        }
 
-        lyrics_containers
-            .into_iter()
-            .map(|x| x.text().collect::<Vec<_>>().join("\n"))
-            .collect::<Vec<_>>()
-            .join("\n")
+        let placeholder = document
+            .select(&PLACEHOLDER_SELECTOR)
+            .flat_map(|placeholder| placeholder.text())
+            .collect::<String>()
+            .to_lowercase();
+        if placeholder.contains("instrumental") {
+            LyricsOutcome::Instrumental
+        } else if placeholder.contains("yet to be released") || placeholder.contains("unreleased") {
+            LyricsOutcome::Unreleased
+        } else {
+            LyricsOutcome::NoContainer
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ScrapeError {
+    Net(NetError),
+    Status { url: String, status: u16 },
+    /// The site's `robots.txt` disallows the page.
+    Disallowed { url: String },
+    /// A lyrics file could not be read.
+    Io(io::Error),
+    /// The page lists no tracks, e.g. because it is not an album page.
+    NoTracklist { url: String },
+}
+
+impl ScrapeError {
+    pub fn status(&self) -> LyricsStatus {
+        match self {
+            ScrapeError::Net(_) | ScrapeError::Io(_) => LyricsStatus::Failed,
+            ScrapeError::Status { .. } => LyricsStatus::HttpError,
+            ScrapeError::Disallowed { .. } => LyricsStatus::Disallowed,
+            ScrapeError::NoTracklist { .. } => LyricsStatus::NoContainer,
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScrapeError::Net(err) => write!(f, "{}", err),
+            ScrapeError::Status { url, status } => write!(f, "HTTP status {} for `{}`", status, url),
+            ScrapeError::Disallowed { url } => write!(f, "`{}` is disallowed by robots.txt", url),
+            ScrapeError::Io(err) => write!(f, "{}", err),
+            ScrapeError::NoTracklist { url } => write!(f, "No tracklist found at `{}`", url),
+        }
+    }
+}
+
+impl std::error::Error for ScrapeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScrapeError::Net(err) => Some(err),
+            ScrapeError::Io(err) => Some(err),
+            ScrapeError::Status { .. } | ScrapeError::Disallowed { .. } | ScrapeError::NoTracklist { .. } => None,
+        }
+    }
+}
+
+impl From<NetError> for ScrapeError {
+    fn from(err: NetError) -> Self {
+        ScrapeError::Net(err)
+    }
+}
+
+impl From<io::Error> for ScrapeError {
+    fn from(err: io::Error) -> Self {
+        ScrapeError::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+    };
+
+    use net::transport::{Fixture, ReplayTransport};
+
+    use super::*;
+
+    const PAGE: &str = r#"<html><head><script>window.__PRELOADED_STATE__ = {"songPage":{"song":1},
+        "entities":{"songs":{"1":{"id":1,"title":"Song","url":"https://genius.com/Song-lyrics"}}}};</script></head>
+        <body><div data-lyrics-container="true">Line</div></body></html>"#;
+
+    #[test]
+    fn reads_the_song_and_metadata_only_when_enabled() {
+        let page = AppScraper::new().scrape_html(PAGE);
+        assert!(page.song.is_none());
+        assert!(page.metadata.is_none());
+
+        let page = AppScraper::new().song(true).metadata(true).scrape_html(PAGE);
+        assert_eq!(page.song.unwrap().id, 1);
+        assert_eq!(page.metadata.unwrap().title.as_deref(), Some("Song"));
+        assert_eq!(page.outcome.into_lyrics().text, "Line");
+    }
+
+    /// Saved song pages without lyrics, named after the outcome they lead to.
+    fn outcomes_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/outcomes")
+    }
+
+    /// Writes a replay fixture answering `GET <url>` with `status` and `body`.
+    fn serve(dir: &Path, url: &str, status: u16, body: &str) {
+        let request = HttpRequest::get(url);
+        let fixture = Fixture {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            query: vec![],
+            status,
+            headers: vec![],
+            body: String::from(body),
+        };
+        let path = dir.join(request.fingerprint()).with_extension("json");
+        fs::write(path, serde_json::to_string(&fixture).unwrap()).unwrap();
+    }
+
+    fn replaying(dir: &Path) -> AppScraper {
+        AppScraper::with_client(HttpClient::with_transport(Arc::new(ReplayTransport::new(dir))))
+    }
+
+    /// The `lyrics_status` written to the output for `status`.
+    fn lyrics_status(status: LyricsStatus) -> serde_json::Value {
+        serde_json::to_value(status).unwrap()
+    }
+
+    #[test]
+    fn pages_without_lyrics_map_to_statuses() {
+        for (page, status) in [
+            ("instrumental", "instrumental"),
+            ("unreleased", "unreleased"),
+            ("no_container", "no_container"),
+        ] {
+            let html = fs::read_to_string(outcomes_dir().join(page).with_extension("html")).unwrap();
+            let outcome = AppScraper::new().scrape_html(&html).outcome;
+            assert_eq!(lyrics_status(outcome.status()), status, "{}", page);
+            assert!(outcome.into_lyrics().text.is_empty());
+        }
+        assert_eq!(lyrics_status(AppScraper::new().scrape_html(PAGE).outcome.status()), "available");
+    }
+
+    #[tokio::test]
+    async fn error_statuses_map_to_http_error() {
+        let url = "https://genius.com/Portishead-all-mine-lyrics";
+        let dir = tempfile::tempdir().unwrap();
+        serve(dir.path(), url, 404, "<html>Page not found</html>");
+
+        let err = replaying(dir.path()).from_url(url).await.unwrap_err();
+        assert!(matches!(err, ScrapeError::Status { status: 404, .. }), "{:?}", err);
+        assert_eq!(lyrics_status(err.status()), "http_error");
+    }
+
+    /// Translations linked from the song pages of `tests/fixtures/translations`.
+    const SPANISH: &str =
+        "https://genius.com/Genius-traducciones-al-espanol-portishead-all-mine-traduccion-al-espanol-lyrics";
+    const FRENCH: &str =
+        "https://genius.com/Genius-traductions-francaises-portishead-all-mine-traduction-francaise-lyrics";
+    const PORTUGUESE: &str =
+        "https://genius.com/Genius-brasil-traducoes-portishead-all-mine-traducao-em-portugues-lyrics";
+    const GERMAN: &str =
+        "https://genius.com/Genius-deutsche-ubersetzungen-portishead-all-mine-deutsche-ubersetzung-lyrics";
+
+    fn translations_fixture(name: &str) -> String {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/translations").join(name);
+        fs::read_to_string(path).unwrap()
+    }
+
+    fn texts(translations: &BTreeMap<String, ScrapedLyrics>) -> Vec<(&str, &str)> {
+        translations
+            .iter()
+            .map(|(language, lyrics)| (language.as_str(), lyrics.text.as_str()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn fetches_the_lyrics_of_translations_only_when_enabled() {
+        let url = "https://genius.com/Portishead-all-mine-lyrics";
+        let dir = tempfile::tempdir().unwrap();
+        serve(dir.path(), url, 200, &translations_fixture("state.html"));
+        serve(dir.path(), SPANISH, 200, &translations_fixture("es.html"));
+        serve(dir.path(), FRENCH, 200, &translations_fixture("fr.html"));
+        // A translation without lyrics yet is left out.
+        serve(dir.path(), PORTUGUESE, 200, &translations_fixture("pt.html"));
+
+        let page = replaying(dir.path()).page(url).await.unwrap();
+        assert!(page.translations.is_empty());
+
+        let page = replaying(dir.path()).translations(true).page(url).await.unwrap();
+        assert_eq!(
+            texts(&page.translations),
+            [
+                ("es", "No caerás por nada que no verás"),
+                ("fr", "Tu ne tomberas pour rien que tu ne verras"),
+            ]
+        );
+        assert_eq!(page.outcome.into_lyrics().text, "You won't fall for anything you won't see");
+    }
+
+    #[tokio::test]
+    async fn fetches_translations_linked_from_the_menu() {
+        let dir = tempfile::tempdir().unwrap();
+        serve(dir.path(), GERMAN, 200, &translations_fixture("de.html"));
+        // A translation that could not be fetched is left out.
+        serve(dir.path(), SPANISH, 500, "");
+
+        let html = translations_fixture("menu.html");
+        let translations = replaying(dir.path()).fetch_translations(&SongPage::parse(&html)).await;
+        assert_eq!(texts(&translations), [("Deutsch", "Du fällst auf nichts herein, was du nicht siehst")]);
     }
 }
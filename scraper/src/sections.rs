@@ -0,0 +1,166 @@
+use genius::model::lyrics::{LyricSection, SectionKind};
+
+/// First words of the part of a name that follows a comma, e.g. `Tyler, The Creator`.
+const NAME_CONTINUATIONS: [&str; 5] = ["the", "jr", "sr", "ii", "iii"];
+
+/// Splits lyrics into sections at header lines such as `[Chorus]` or `[Verse 2: Jay Rock & SZA]`.
+///
+/// Blank lines are dropped. Lines preceding the first header end up in a section of kind
+/// [`SectionKind::Other`] with an empty label.
+pub fn parse_sections(lyrics: &str) -> Vec<LyricSection> {
+    let mut sections: Vec<LyricSection> = vec![];
+    for line in lyrics.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(header) = parse_header(line) {
+            sections.push(header);
+            continue;
+        }
+        match sections.last_mut() {
+            Some(section) => section.lines.push(String::from(line)),
+            None => sections.push(LyricSection {
+                kind: SectionKind::Other,
+                label: String::new(),
+                performers: vec![],
+                credit: String::new(),
+                lines: vec![String::from(line)],
+            }),
+        }
+    }
+    sections
+}
+
+fn parse_header(line: &str) -> Option<LyricSection> {
+    let header = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    // Inline markers such as `[?]` are not headers.
+    if !header.starts_with(char::is_alphabetic) {
+        return None;
+    }
+
+    let (label, credit) = match header.split_once(':') {
+        Some((label, credit)) => (label.trim(), credit.trim()),
+        None => (header, ""),
+    };
+    Some(LyricSection {
+        kind: section_kind(label),
+        label: String::from(label),
+        performers: split_performers(credit),
+        credit: String::from(credit),
+        lines: vec![],
+    })
+}
+
+/// Splits a credit such as `Jay Rock, SZA & Kendrick Lamar` at commas and ampersands.
+///
+/// A comma followed by a word that continues a name, as in `Tyler, The Creator` or `Harry Connick, Jr.`,
+/// does not start a new performer.
+fn split_performers(credit: &str) -> Vec<String> {
+    let mut performers: Vec<String> = vec![];
+    for group in credit.split('&') {
+        for (i, name) in group.split(',').map(str::trim).enumerate() {
+            if name.is_empty() {
+                continue;
+            }
+            match performers.last_mut() {
+                Some(previous) if i > 0 && continues_name(name) => {
+                    previous.push_str(", ");
+                    previous.push_str(name);
+                }
+                _ => performers.push(String::from(name)),
+            }
+        }
+    }
+    performers
+}
+
+fn continues_name(name: &str) -> bool {
+    let first_word = name.split_whitespace().next().unwrap_or_default().to_lowercase();
+    NAME_CONTINUATIONS.contains(&first_word.trim_end_matches('.'))
+}
+
+/// Normalizes labels such as `Pre-Chorus`, `Verse 2` or `Refrain (Reprise)` to a [`SectionKind`].
+fn section_kind(label: &str) -> SectionKind {
+    let label: String = label
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphabetic() || c.is_whitespace())
+        .collect();
+    let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+    let starts_with = |prefixes: &[&str]| prefixes.iter().any(|prefix| label.starts_with(prefix));
+
+    if starts_with(&["pre chorus", "prechorus", "pre hook", "prehook"]) {
+        SectionKind::PreChorus
+    } else if starts_with(&["post chorus", "postchorus", "post hook", "posthook"]) {
+        SectionKind::PostChorus
+    } else if starts_with(&["chorus"]) {
+        SectionKind::Chorus
+    } else if starts_with(&["verse"]) {
+        SectionKind::Verse
+    } else if starts_with(&["hook"]) {
+        SectionKind::Hook
+    } else if starts_with(&["refrain"]) {
+        SectionKind::Refrain
+    } else if starts_with(&["intro"]) {
+        SectionKind::Intro
+    } else if starts_with(&["outro"]) {
+        SectionKind::Outro
+    } else if starts_with(&["bridge"]) {
+        SectionKind::Bridge
+    } else if starts_with(&["interlude"]) {
+        SectionKind::Interlude
+    } else if starts_with(&["breakdown"]) {
+        SectionKind::Breakdown
+    } else if starts_with(&["instrumental"]) {
+        SectionKind::Instrumental
+    } else if starts_with(&["skit"]) {
+        SectionKind::Skit
+    } else {
+        SectionKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(line: &str) -> LyricSection {
+        parse_header(line).unwrap()
+    }
+
+    #[test]
+    fn parses_label_and_performers() {
+        let section = header("[Verse 2: Jay Rock & SZA]");
+        assert_eq!(section.kind, SectionKind::Verse);
+        assert_eq!(section.label, "Verse 2");
+        assert_eq!(section.performers, ["Jay Rock", "SZA"]);
+        assert_eq!(section.credit, "Jay Rock & SZA");
+
+        let section = header("[Pre-Chorus]");
+        assert_eq!(section.kind, SectionKind::PreChorus);
+        assert!(section.performers.is_empty());
+        assert!(section.credit.is_empty());
+    }
+
+    #[test]
+    fn keeps_names_containing_a_comma() {
+        let section = header("[Verse 1: Tyler, The Creator, Frank Ocean & A$AP Rocky]");
+        assert_eq!(section.performers, ["Tyler, The Creator", "Frank Ocean", "A$AP Rocky"]);
+        assert_eq!(header("[Intro: Harry Connick, Jr.]").performers, ["Harry Connick, Jr."]);
+        assert_eq!(header("[Chorus: Tyler, The Creator]").credit, "Tyler, The Creator");
+    }
+
+    #[test]
+    fn ignores_inline_markers_and_unbracketed_lines() {
+        assert!(parse_header("[?]").is_none());
+        assert!(parse_header("[Chorus").is_none());
+        assert!(parse_header("Chorus]").is_none());
+    }
+
+    #[test]
+    fn groups_lines_under_their_header() {
+        let sections = parse_sections("Spoken intro\n\n[Hook: Drake]\nLine 1\n\nLine 2\n[Outro]");
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].kind, SectionKind::Other);
+        assert_eq!(sections[0].lines, ["Spoken intro"]);
+        assert_eq!(sections[1].lines, ["Line 1", "Line 2"]);
+        assert!(sections[2].lines.is_empty());
+    }
+}
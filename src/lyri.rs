@@ -1,17 +1,59 @@
-use cli::cli::{ArtistArgs, Cli, Commands};
-use files::file_manager::{FileManager, SongsFileManager};
+use cli::{
+    cli::{
+        AlbumArgs, ArtistArgs, AuthArgs, AuthCommands, CacheArgs, CacheCommands, CacheOptions, Cli, Commands, LoginArgs,
+        LyricsOptions, ProgressMode, ScrapeLocalArgs, ScraperOptions,
+    },
+    progress::FetchProgressObserver,
+};
+use files::{
+    file_manager::{
+        song_file_path, AlbumFileData, AlbumTrackWithLyrics, FileDataWithLyrics, FileManager, SongsFileManager,
+    },
+    lrc::Lrc,
+};
+use futures::TryStreamExt;
 use genius::{
+    auth::{self, CredentialsStore, OAuthClient, RedirectListener},
+    error::GeniusError,
     genius::{ArtistSongsOptions, Genius, SongsSort},
-    model::{artist::PrimaryArtist, hit::Hit, song::ArtistSong},
+    progress::{JsonLinesObserver, NoopObserver, ProgressObserver},
+    model::{
+        lyrics::LyricsStatus,
+        page::{AlbumTracklist, SongPageMetadata},
+        song::{ArtistSong, ArtistSongWithLyrics},
+    },
+    resolver::{ArtistCandidate, MatchKind, ResolveOptions},
+};
+use log::{debug, error, info, warn};
+use net::{
+    cache::HttpCache,
+    client::HttpClient,
+    rate_limit::RateLimiter,
+    transport::{HttpTransport, RecordingTransport, ReplayTransport, ReqwestTransport},
 };
-use log::{error, info};
 use processing::filters::{self, FilterOptions};
-use scraper::scraper::AppScraper;
+use scraper::{
+    cleaning::Cleaner,
+    metadata::SongPage,
+    provider::{FallbackProvider, LocalDirectoryProvider, LyricsProvider},
+    render::ScrapedLyrics,
+    scraper::{AppScraper, ScrapedPage},
+    sections::parse_sections,
+};
 use serde_json::json;
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
-use tokio::sync::Semaphore;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-const MAX_PERMITS: usize = 50;
+const MAX_CHOICES: usize = 5;
+const HTML_EXTENSIONS: [&str; 2] = ["html", "htm"];
+/// Access token sent with replayed requests, which never reach Genius.
+const REPLAY_TOKEN: &str = "replay";
 
 fn build_path(artist: &str, dir_path: Option<String>) -> PathBuf {
     let mut pb = PathBuf::new();
@@ -24,56 +66,307 @@ fn build_path(artist: &str, dir_path: Option<String>) -> PathBuf {
     pb
 }
 
-fn find_arg_artist_from_hits(arg_artist: &str, genius_hits: Vec<Hit>) -> (u32, String) {
-    let matched_hit = genius_hits
-        .iter()
-        .find(|&hit| hit.result.primary_artist.name.to_lowercase() == arg_artist.to_lowercase());
+/// Resolves the artist to fetch, either from `--artist-id` or from the best matches for `--name`.
+async fn find_artist(
+    genius: &Genius,
+    name: Option<String>,
+    artist_id: Option<u32>,
+    interactive: bool,
+) -> Result<(u32, String), Box<dyn std::error::Error>> {
+    if let Some(artist_id) = artist_id {
+        let artist = genius.artists(artist_id).await?;
+        return Ok((artist.id, artist.name));
+    }
 
-    let PrimaryArtist { id, name } = match matched_hit {
-        Some(hit) => hit.result.primary_artist.clone(),
-        None => panic!("Could not find artist `{}` in Genius hits.", arg_artist),
+    let name = name.ok_or("Either `--name` or `--artist-id` is required.")?;
+    let candidates = match genius.resolve_artist(&name, ResolveOptions::default()).await {
+        Err(GeniusError::NotFound { .. }) => {
+            return Err(format!("Could not find artist `{}` in Genius hits.", name).into())
+        }
+        result => result?,
+    };
+    let candidate = if interactive {
+        choose_artist(&name, &candidates[..candidates.len().min(MAX_CHOICES)])?
+    } else {
+        let best = &candidates[0];
+        if best.match_kind > MatchKind::Normalized {
+            warn!(
+                "No exact match for `{}`, using `{}` (ID {}). Use `--interactive` or `--artist-id` to choose another artist.",
+                name, best.name, best.id
+            );
+        }
+        best
     };
 
-    (id, name)
+    Ok((candidate.id, candidate.name.clone()))
+}
+
+fn choose_artist<'a>(
+    name: &str,
+    candidates: &'a [ArtistCandidate],
+) -> Result<&'a ArtistCandidate, Box<dyn std::error::Error>> {
+    eprintln!("Artists matching `{}`:", name);
+    for (i, candidate) in candidates.iter().enumerate() {
+        eprintln!("  {}. {} (ID {}, {} hits)", i + 1, candidate.name, candidate.id, candidate.hits);
+    }
+
+    loop {
+        eprint!("Choose an artist [1-{}] (default 1): ", candidates.len());
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            return Err("No artist chosen.".into());
+        }
+        match line.trim() {
+            "" => return Ok(&candidates[0]),
+            choice => match choice.parse::<usize>() {
+                Ok(i) if (1..=candidates.len()).contains(&i) => return Ok(&candidates[i - 1]),
+                _ => eprintln!("Invalid choice `{}`.", choice),
+            },
+        }
+    }
 }
 
-async fn scrape_lyrics_in_parallel(songs: Vec<ArtistSong>) -> HashMap<u32, String> {
-    let progress_bar = Arc::new(cli::progress::scrape_progress_bar(songs.len() as u16));
-    let semaphore = Arc::new(Semaphore::new(std::cmp::min(songs.len(), MAX_PERMITS)));
+/// What was scraped from a song page.
+struct ScrapedSong {
+    status: LyricsStatus,
+    lyrics: ScrapedLyrics,
+    metadata: Option<SongPageMetadata>,
+    translations: BTreeMap<String, String>,
+}
+
+fn to_scraped_song(
+    song_id: u32,
+    ScrapedPage {
+        outcome,
+        metadata,
+        translations,
+        ..
+    }: ScrapedPage,
+) -> ScrapedSong {
+    let status = outcome.status();
+    if status != LyricsStatus::Available {
+        warn!("No lyrics for song `{}`: {:?}", song_id, status);
+    }
+    let lyrics = outcome.into_lyrics();
+    for removal in &lyrics.cleaning.removals {
+        debug!("Removed {} from song `{}`: {:?}", removal.artifact, song_id, removal.text);
+    }
+    ScrapedSong {
+        status,
+        lyrics,
+        metadata,
+        translations: translations
+            .into_iter()
+            .map(|(language, lyrics)| (language, lyrics.text))
+            .collect(),
+    }
+}
+
+/// Fills in what was scraped for `song`, besides its lyrics text.
+fn apply_scraped(song: &mut ArtistSongWithLyrics, scraped: ScrapedSong, options: &LyricsOptions) {
+    song.lyrics_status = scraped.status;
+    if options.sections {
+        song.sections = Some(parse_sections(&song.lyrics));
+    }
+    if options.annotations {
+        song.annotations = Some(scraped.lyrics.annotations);
+    }
+    song.metadata = scraped.metadata;
+    song.synced = scraped.lyrics.synced;
+    if options.translations {
+        song.translations = Some(scraped.translations);
+    }
+}
+
+/// Saves every song with lyrics as an LRC file, laid out like a `--lyrics-dir` directory.
+fn export_lrc(dir: &Path, songs: &[ArtistSongWithLyrics]) {
+    let mut exported = 0;
+    for song in songs.iter().filter(|song| !song.lyrics.is_empty()) {
+        let path = song_file_path(dir, &song.song, "lrc");
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, Lrc::from_song(song).to_string()));
+        match result {
+            Ok(()) => exported += 1,
+            Err(err) => error!("Error writing {:?}: {}", path, err),
+        }
+    }
+    info!("Exported {} LRC files to {:?}", exported, dir);
+}
+
+/// `path` itself if it is a file, or the HTML files found under it, sorted by path.
+fn find_html_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| HTML_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Scrapes saved song pages, reading each song from the page itself since there is no API response.
+///
+/// Translations are not fetched: they are taken from the saved pages of the translations, if any.
+fn scrape_local(path: &Path, scraper: &AppScraper, options: &LyricsOptions) -> io::Result<Vec<ArtistSongWithLyrics>> {
+    let mut pages = vec![];
+    for file in find_html_files(path)? {
+        let html = match fs::read_to_string(&file) {
+            Ok(html) => html,
+            Err(err) => {
+                error!("Error reading {:?}: {}", file, err);
+                continue;
+            }
+        };
+        let page = SongPage::parse(&html);
+        let Some(song) = page.song() else {
+            warn!("Skipping {:?}: no song ID found in the page", file);
+            continue;
+        };
+        let scraped = to_scraped_song(song.id, scraper.scrape_song_page(&page));
+        pages.push((song, scraped, page.translations()));
+    }
+
+    let lyrics_by_id: HashMap<u32, String> = pages
+        .iter()
+        .map(|(song, scraped, _)| (song.id, scraped.lyrics.text.clone()))
+        .collect();
+    let songs = pages
+        .into_iter()
+        .map(|(song, mut scraped, translations)| {
+            scraped.translations = translations
+                .into_iter()
+                .filter_map(|link| Some((link.language, lyrics_by_id.get(&link.song_id?)?.clone())))
+                .collect();
+            let mut song = song.to_artist_song_with_lyrics(scraped.lyrics.text.clone());
+            apply_scraped(&mut song, scraped, options);
+            song
+        })
+        .collect();
+    Ok(songs)
+}
+
+async fn scrape_lyrics_in_parallel(
+    songs: Vec<ArtistSong>,
+    provider: Arc<dyn LyricsProvider>,
+    progress: ProgressMode,
+) -> HashMap<u32, ScrapedSong> {
+    let progress_bar = Arc::new(match progress {
+        ProgressMode::Bar => cli::progress::scrape_progress_bar(songs.len() as u16),
+        ProgressMode::Json | ProgressMode::None => cli::progress::hidden_progress_bar(),
+    });
 
     let mut join_handles = Vec::new();
-    for song in songs.clone() {
+    for song in songs {
         let pbc = Arc::clone(&progress_bar);
-        let semaphore = Arc::clone(&semaphore);
-        let _permit = semaphore.acquire_owned().await.unwrap();
+        let provider = Arc::clone(&provider);
 
         join_handles.push(tokio::spawn(async move {
-            match AppScraper::new().from_url(&song.url).await {
-                Ok(lyrics) => {
-                    drop(_permit);
+            match provider.fetch_page(&song).await {
+                Ok(page) => {
                     pbc.inc(1);
-                    (song.id, lyrics)
+                    (song.id, to_scraped_song(song.id, page))
                 }
                 Err(err) => {
-                    error!("Error scraping lyrics for song `{}`: {}", song.id, err);
-                    (song.id, String::new())
+                    error!("Error fetching lyrics for song `{}`: {}", song.id, err);
+                    (
+                        song.id,
+                        ScrapedSong {
+                            status: err.status(),
+                            lyrics: ScrapedLyrics::default(),
+                            metadata: None,
+                            translations: BTreeMap::new(),
+                        },
+                    )
                 }
             }
         }));
     }
 
-    let mut lyrics_map: HashMap<u32, String> = HashMap::new();
+    let mut lyrics_map: HashMap<u32, ScrapedSong> = HashMap::new();
 
     for join_handle in join_handles {
         let (song_id, lyrics) = join_handle.await.unwrap();
         lyrics_map.insert(song_id, lyrics);
     }
 
+    let removals = lyrics_map
+        .values()
+        .map(|scraped| scraped.lyrics.cleaning.removals.len())
+        .sum::<usize>();
+    if removals > 0 {
+        info!("Removed {} page artifacts from the lyrics", removals);
+    }
+
     lyrics_map
 }
 
+/// Scrapes every track of an album, keeping the album order. The song of each track is read from its page.
+async fn scrape_album(
+    tracklist: AlbumTracklist,
+    scraper: &AppScraper,
+    options: &LyricsOptions,
+    progress: ProgressMode,
+) -> Vec<AlbumTrackWithLyrics> {
+    let progress_bar = match progress {
+        ProgressMode::Bar => cli::progress::scrape_progress_bar(tracklist.tracks.len() as u16),
+        ProgressMode::Json | ProgressMode::None => cli::progress::hidden_progress_bar(),
+    };
+
+    let tracks = tracklist.tracks.into_iter().map(|track| async {
+        let page = match &track.url {
+            Some(url) => scraper.page(url).await.map(Some),
+            None => Ok(None),
+        };
+        progress_bar.inc(1);
+        let song = match page {
+            Ok(Some(mut page)) => match page.song.take() {
+                Some(song) => {
+                    let scraped = to_scraped_song(song.id, page);
+                    let mut song = song.to_artist_song_with_lyrics(scraped.lyrics.text.clone());
+                    apply_scraped(&mut song, scraped, options);
+                    Some(song)
+                }
+                None => {
+                    warn!("Skipping track `{}`: no song ID found in its page", track.title);
+                    None
+                }
+            },
+            Ok(None) => {
+                warn!("Skipping track `{}`: it has no song page", track.title);
+                None
+            }
+            Err(err) => {
+                error!("Error scraping lyrics for track `{}`: {}", track.title, err);
+                None
+            }
+        };
+        AlbumTrackWithLyrics { track, song }
+    });
+    let tracks = futures::future::join_all(tracks).await;
+    progress_bar.finish_and_clear();
+    tracks
+}
+
 fn to_songs_sort_type(sort: Option<String>) -> Option<SongsSort> {
-    let sort = sort.unwrap_or(String::new());
+    let sort = sort.unwrap_or_default();
 
     match sort.as_str() {
         "popularity" => Some(SongsSort::Popularity),
@@ -82,41 +375,234 @@ fn to_songs_sort_type(sort: Option<String>) -> Option<SongsSort> {
     }
 }
 
+/// Validates the access token. `/account` requires the `me` scope, which client access tokens
+/// do not have, so a rejected `/account` call is followed by a search to tell the two cases apart.
+async fn check_auth(genius: &Genius) -> Result<(), Box<dyn std::error::Error>> {
+    match genius.account().await {
+        Ok(user) => {
+            info!("Access token is valid. Authenticated as `{}` (id {}).", user.login, user.id);
+            Ok(())
+        }
+        Err(GeniusError::Unauthorized { .. } | GeniusError::Api { status: 403, .. }) => {
+            match genius.search("genius").await {
+                Ok(_) => {
+                    warn!("Access token is valid, but it is not tied to a user (missing `me` scope).");
+                    Ok(())
+                }
+                Err(err @ GeniusError::Unauthorized { .. }) => {
+                    error!("Access token was rejected by Genius. Generate a new one at https://genius.com/api-clients.");
+                    Err(err.into())
+                }
+                Err(err) => Err(err.into()),
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn credentials_store() -> Result<CredentialsStore, Box<dyn std::error::Error>> {
+    CredentialsStore::user_default().ok_or_else(|| "Could not determine the configuration directory.".into())
+}
+
+async fn login(
+    LoginArgs {
+        client_id,
+        client_secret,
+        port,
+        scopes,
+    }: LoginArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let store = credentials_store()?;
+    let listener = RedirectListener::bind(port).await?;
+    let oauth = OAuthClient::new(client_id, client_secret, listener.redirect_uri()?);
+    let state = auth::generate_state();
+
+    info!(
+        "Open the following URL in your browser to authorize lyri:\n{}",
+        oauth.authorization_url(&scopes, &state)?
+    );
+    let code = listener.wait_for_code(&state).await?;
+    let credentials = oauth.exchange_code(&code).await?;
+    store.save(&credentials)?;
+    info!("Access token saved to {:?}", store.path());
+
+    Ok(())
+}
+
+fn logout() -> Result<(), Box<dyn std::error::Error>> {
+    let store = credentials_store()?;
+    if store.clear()? {
+        info!("Removed access token from {:?}", store.path());
+    } else {
+        info!("No stored access token found.");
+    }
+    Ok(())
+}
+
+fn build_cache(options: &CacheOptions) -> Option<HttpCache> {
+    let cache = match &options.cache_dir {
+        Some(dir) => Some(HttpCache::new(dir)),
+        None => HttpCache::user_default(),
+    };
+    cache.map(|cache| {
+        cache
+            .ttl(Duration::from_secs(options.cache_ttl * 60 * 60))
+            .max_bytes(Some(options.cache_max_size * 1024 * 1024))
+    })
+}
+
+fn build_transport(record: Option<String>, replay: Option<String>) -> Arc<dyn HttpTransport> {
+    let reqwest = ReqwestTransport::new(reqwest::Client::new());
+    match (record, replay) {
+        (_, Some(dir)) => Arc::new(ReplayTransport::new(dir)),
+        (Some(dir), None) => Arc::new(RecordingTransport::new(reqwest, dir)),
+        (None, None) => Arc::new(reqwest),
+    }
+}
+
+fn build_http_client(transport: Arc<dyn HttpTransport>, cache: Option<Arc<HttpCache>>, offline: bool) -> HttpClient {
+    let client = HttpClient::with_transport(transport).offline(offline);
+    match cache {
+        Some(cache) => client.cache(cache),
+        None => client,
+    }
+}
+
+fn build_scraper(options: &ScraperOptions, client: HttpClient, lyrics: &LyricsOptions) -> AppScraper {
+    let mut scraper = AppScraper::with_client(client)
+        .cleaner(Cleaner::new().enabled(!lyrics.no_clean))
+        .metadata(lyrics.metadata)
+        .translations(lyrics.translations)
+        .user_agent(options.user_agent.clone())
+        .max_concurrency(options.scrape_concurrency)
+        .respect_robots(options.respect_robots);
+    if let Some(rate_limit) = options.scrape_rate_limit {
+        scraper = scraper.rate_limiter(Arc::new(RateLimiter::per_second(rate_limit).per_host(true)));
+    }
+    if let Some(timeout) = options.scrape_timeout {
+        scraper = scraper.timeout(Duration::from_secs(timeout));
+    }
+    scraper
+}
+
+fn build_genius(
+    progress: ProgressMode,
+    transport: Arc<dyn HttpTransport>,
+    replay: bool,
+    cache: Option<Arc<HttpCache>>,
+    offline: bool,
+    rate_limit: Option<u32>,
+) -> Result<Genius, Box<dyn std::error::Error>> {
+    let observer: Arc<dyn ProgressObserver> = match progress {
+        ProgressMode::Bar => Arc::new(FetchProgressObserver::new()),
+        ProgressMode::Json => Arc::new(JsonLinesObserver::stdout()),
+        ProgressMode::None => Arc::new(NoopObserver),
+    };
+    let mut builder = Genius::builder()
+        .observer(observer)
+        .transport(transport)
+        .offline(offline);
+    if let Some(cache) = cache {
+        builder = builder.cache(cache);
+    }
+    if let Some(rate_limit) = rate_limit {
+        builder = builder.rate_limiter(Arc::new(RateLimiter::per_second(rate_limit)));
+    }
+    if replay {
+        // Fixtures are served without looking at the request headers, so no real token is needed.
+        builder = builder.token(REPLAY_TOKEN);
+    }
+    Ok(builder.build()?)
+}
+
+fn manage_cache(cache: Option<HttpCache>, command: CacheCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let cache = cache.ok_or("Could not determine the cache directory. Use `--cache-dir` to set it.")?;
+    match command {
+        CacheCommands::Stats => {
+            let stats = cache.stats()?;
+            info!(
+                "Cache at {:?}: {} entries ({} expired), {:.1} MB",
+                cache.dir(),
+                stats.entries,
+                stats.expired,
+                stats.bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        CacheCommands::Prune => {
+            let report = cache.prune()?;
+            info!(
+                "Removed {} entries, freed {:.1} MB",
+                report.removed,
+                report.freed_bytes as f64 / (1024.0 * 1024.0)
+            );
+        }
+        CacheCommands::Clear => {
+            let removed = cache.clear()?;
+            info!("Removed {} entries from {:?}", removed, cache.dir());
+        }
+    }
+    Ok(())
+}
+
 pub async fn lyri(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
-    let genius = Genius::new();
+    let progress = cli.progress;
+    let offline = cli.cache.offline;
+    let cache = build_cache(&cli.cache);
+    // Fixtures have to capture (or serve) every exchange, so the cache is bypassed while using them.
+    let use_cache = !cli.cache.no_cache && cli.record.is_none() && cli.replay.is_none();
+    let replay = cli.replay.is_some();
+    let transport = build_transport(cli.record, cli.replay);
+    let rate_limit = cli.rate_limit;
+    let scraper_options = cli.scraper;
 
     match cli.commands {
+        Commands::Cache(CacheArgs { command }) => manage_cache(cache, command)?,
+        Commands::Auth(AuthArgs { command }) => match command {
+            AuthCommands::Check => check_auth(&build_genius(progress, transport, replay, None, false, rate_limit)?).await?,
+            AuthCommands::Login(args) => login(args).await?,
+            AuthCommands::Logout => logout()?,
+        },
         Commands::Artist(ArtistArgs {
             name,
+            artist_id,
+            interactive,
             limit,
             antipattern,
             features,
             sort,
             output_dir,
+            page_concurrency,
+            lyrics_dir,
+            lyrics,
         }) => {
-            let hits = genius.search(&name).await?;
-            let (artist_id, artist_name) = find_arg_artist_from_hits(&name, hits);
-            let songs_response = genius
-                .artists_songs(
-                    artist_id,
-                    ArtistSongsOptions {
-                        sort: to_songs_sort_type(sort),
-                    },
-                )
-                .await?;
-            let mut filtered_songs = filters::apply(
+            let cache = cache.filter(|_| use_cache).map(Arc::new);
+            let genius = build_genius(progress, Arc::clone(&transport), replay, cache.clone(), offline, rate_limit)?;
+            let (artist_id, artist_name) = find_artist(&genius, name, artist_id, interactive).await?;
+            let filter_options = FilterOptions {
+                include_features: features,
+                antipattern,
+            };
+            let mut pages = Box::pin(genius.artists_songs_pages(
                 artist_id,
-                songs_response,
-                FilterOptions {
-                    include_features: features,
-                    antipattern: antipattern,
+                ArtistSongsOptions {
+                    sort: to_songs_sort_type(sort),
+                    concurrency: page_concurrency,
+                    ..Default::default()
                 },
-            );
-            if let Some(l) = limit {
-                if l < filtered_songs.len() as u32 {
-                    filtered_songs.truncate(l as usize);
+            ));
+            let mut filtered_songs = vec![];
+            while let Some(page) = pages.try_next().await? {
+                filtered_songs.extend(filters::apply(artist_id, page, filter_options.clone()));
+                // Stop requesting pages as soon as enough songs survived the filters.
+                if limit.is_some_and(|l| filtered_songs.len() >= l as usize) {
+                    break;
                 }
             }
+            // Ends pagination, which lets the observer clear its spinner before scraping starts.
+            drop(pages);
+            if let Some(l) = limit {
+                filtered_songs.truncate(l as usize);
+            }
             let file_json = json!({
                 "total": filtered_songs.len(),
                 "songs": filtered_songs
@@ -127,15 +613,163 @@ pub async fn lyri(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 error!("Error writing file to {:?}: {}", path_buf, err);
             }
             let res_file = SongsFileManager::read(path_buf.as_path());
-            let lyrics_map = scrape_lyrics_in_parallel(res_file.songs.clone()).await;
-            let file_data_with_lyrics = res_file.to_file_data_with_lyrics(lyrics_map);
+            let client = build_http_client(transport, cache, offline);
+            let scraper = build_scraper(&scraper_options, client, &lyrics);
+            let provider: Arc<dyn LyricsProvider> = match lyrics_dir {
+                Some(dir) => Arc::new(FallbackProvider::new(LocalDirectoryProvider::new(dir)).or(scraper)),
+                None => Arc::new(scraper),
+            };
+            let mut scraped = scrape_lyrics_in_parallel(res_file.songs.clone(), provider, progress).await;
+            let lyrics_map = scraped
+                .iter()
+                .map(|(song_id, scraped)| (*song_id, scraped.lyrics.text.clone()))
+                .collect();
+            let mut file_data_with_lyrics = res_file.to_file_data_with_lyrics(lyrics_map);
+            for song in file_data_with_lyrics.songs.iter_mut() {
+                if let Some(scraped) = scraped.remove(&song.song.id) {
+                    apply_scraped(song, scraped, &lyrics);
+                }
+            }
+            if let Some(dir) = &lyrics.export_lrc {
+                export_lrc(Path::new(dir), &file_data_with_lyrics.songs);
+            }
             let file_json = json!({
                 "total": file_data_with_lyrics.songs.len(),
                 "songs": file_data_with_lyrics
             });
             SongsFileManager::write(path_buf.as_path(), file_json);
         }
+        Commands::Album(AlbumArgs {
+            url,
+            artist,
+            title,
+            output_dir,
+            lyrics,
+        }) => {
+            let cache = cache.filter(|_| use_cache).map(Arc::new);
+            let url = match (url, artist, title) {
+                (Some(url), _, _) => url,
+                (None, Some(artist), Some(title)) => {
+                    let genius = build_genius(progress, Arc::clone(&transport), replay, cache.clone(), offline, rate_limit)?;
+                    let album = genius.find_album(&artist, &title).await?;
+                    album
+                        .ok_or_else(|| format!("Could not find album `{}` by `{}` in Genius hits.", title, artist))?
+                        .url
+                }
+                _ => return Err("Either `--url` or both `--artist` and `--title` are required.".into()),
+            };
+
+            let client = build_http_client(transport, cache, offline);
+            let scraper = build_scraper(&scraper_options, client, &lyrics).song(true);
+            let tracklist = scraper.album(&url).await?;
+            info!("Found {} tracks on `{}`", tracklist.tracks.len(), tracklist.name);
+
+            let path_buf = build_path(
+                format!("{} {}", tracklist.artist.as_deref().unwrap_or_default(), tracklist.name).trim(),
+                output_dir,
+            );
+            let album = AlbumFileData {
+                album_id: tracklist.album_id,
+                name: tracklist.name.clone(),
+                artist: tracklist.artist.clone(),
+                url: tracklist.url.clone(),
+                total: tracklist.tracks.len(),
+                tracks: scrape_album(tracklist, &scraper, &lyrics, progress).await,
+            };
+            if let Some(dir) = &lyrics.export_lrc {
+                let songs: Vec<_> = album.tracks.iter().filter_map(|track| track.song.clone()).collect();
+                export_lrc(Path::new(dir), &songs);
+            }
+            SongsFileManager::try_write(path_buf.as_path(), json!(album))?;
+        }
+        Commands::ScrapeLocal(ScrapeLocalArgs { path, output, lyrics }) => {
+            let path = Path::new(&path);
+            let scraper = AppScraper::new()
+                .cleaner(Cleaner::new().enabled(!lyrics.no_clean))
+                .metadata(lyrics.metadata);
+            let songs = scrape_local(path, &scraper, &lyrics)?;
+            info!("Scraped {} saved song pages", songs.len());
+            if let Some(dir) = &lyrics.export_lrc {
+                export_lrc(Path::new(dir), &songs);
+            }
+
+            let path_buf = match output {
+                Some(output) => PathBuf::from(output),
+                None => build_path(&path.file_stem().unwrap_or_default().to_string_lossy(), None),
+            };
+            let file_json = json!({
+                "total": songs.len(),
+                "songs": FileDataWithLyrics {
+                    total: songs.len(),
+                    songs,
+                }
+            });
+            SongsFileManager::try_write(path_buf.as_path(), file_json)?;
+        }
     }
 
-    return Ok(());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use scraper::scraper::LyricsOutcome;
+
+    use super::*;
+
+    fn song() -> ArtistSong {
+        let content = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("data/portishead.json")).unwrap();
+        let file: serde_json::Value = serde_json::from_str(&content).unwrap();
+        serde_json::from_value(file["songs"][0].clone()).unwrap()
+    }
+
+    fn lyrics_options(translations: bool) -> LyricsOptions {
+        LyricsOptions {
+            sections: false,
+            annotations: false,
+            no_clean: false,
+            metadata: false,
+            translations,
+            export_lrc: None,
+        }
+    }
+
+    fn scraped_page() -> ScrapedPage {
+        let lyrics = |text: &str| ScrapedLyrics {
+            text: String::from(text),
+            ..Default::default()
+        };
+        ScrapedPage {
+            outcome: LyricsOutcome::Lyrics(lyrics("You won't fall for anything you won't see")),
+            song: None,
+            metadata: None,
+            translations: BTreeMap::from([
+                (String::from("es"), lyrics("No caerás por nada que no verás")),
+                (String::from("fr"), lyrics("Tu ne tomberas pour rien que tu ne verras")),
+            ]),
+        }
+    }
+
+    #[test]
+    fn translations_are_saved_by_language_when_requested() {
+        let song = song();
+        let scraped = to_scraped_song(song.id, scraped_page());
+        let mut record = song.to_artist_song_with_lyrics(scraped.lyrics.text.clone());
+        apply_scraped(&mut record, scraped, &lyrics_options(true));
+
+        let record = serde_json::to_value(&record).unwrap();
+        assert_eq!(record["lyrics_status"], "available");
+        assert_eq!(
+            record["translations"],
+            json!({
+                "es": "No caerás por nada que no verás",
+                "fr": "Tu ne tomberas pour rien que tu ne verras",
+            })
+        );
+
+        let scraped = to_scraped_song(song.id, scraped_page());
+        let mut record = song.to_artist_song_with_lyrics(scraped.lyrics.text.clone());
+        apply_scraped(&mut record, scraped, &lyrics_options(false));
+        assert!(serde_json::to_value(&record).unwrap().get("translations").is_none());
+    }
 }
@@ -3,7 +3,7 @@ mod lyri;
 use clap::Parser;
 use cli::cli::Cli;
 use env_logger::Env;
-use {crate::lyri::lyri, tokio};
+use crate::lyri::lyri;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {